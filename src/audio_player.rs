@@ -1,10 +1,23 @@
+use rodio::source::SineWave;
 use rodio::{Decoder, OutputStream, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// True si el archivo existe y rodio lo reconoce como audio. La cabecera del
+// nivel lo revisa antes de crear el AudioPlayer, que no admite fallos.
+pub fn is_playable(path: &str) -> bool {
+    File::open(path)
+        .ok()
+        .and_then(|file| Decoder::new(BufReader::new(file)).ok())
+        .is_some()
+}
 
 pub struct AudioPlayer {
     sink: Arc<Mutex<Sink>>,
+    // Efectos cortos que no deben esperar a la música, como los pasos
+    effects: Sink,
     _stream: OutputStream,
     victory_played: Arc<Mutex<bool>>, 
 }
@@ -21,8 +34,11 @@ impl AudioPlayer {
         sink.append(looped_source);
         sink.set_volume(0.5);
 
+        let effects = Sink::try_new(&stream_handle).unwrap();
+
         AudioPlayer {
             sink: Arc::new(Mutex::new(sink)),
+            effects,
             _stream: stream,
             victory_played: Arc::new(Mutex::new(false)), 
         }
@@ -36,30 +52,28 @@ impl AudioPlayer {
         self.sink.lock().unwrap().stop();
     }
 
-    pub fn set_volume(&self, volume: f32) {
-        self.sink.lock().unwrap().set_volume(volume);
-    }
-
+    // Se reproduce en el sink de efectos, así que no detiene el juego mientras suena
     pub fn play_sound_effect(&self, sound_file: &str, volume: f32) {
-        let victory_played = *self.victory_played.lock().unwrap();
-        if !victory_played {
-            let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-            let sink = Sink::try_new(&stream_handle).unwrap();
-    
+        let mut victory_played = self.victory_played.lock().unwrap();
+        if !*victory_played {
             let file = BufReader::new(File::open(sound_file).unwrap());
             let source = Decoder::new(file).unwrap();
-    
-            let louder_source = source.amplify(volume);
-            sink.append(louder_source);
-            
-            sink.sleep_until_end();
-    
-            *self.victory_played.lock().unwrap() = true;
+
+            self.effects.append(source.amplify(volume));
+            *victory_played = true;
         }
     }
     
 
-    pub fn reset_victory(&self) {
-        *self.victory_played.lock().unwrap() = false;
+    // Golpe grave y corto. Si ya suena otro efecto, el paso se omite para
+    // que no se acumulen detrás de él.
+    pub fn play_footstep(&self, volume: f32) {
+        if !self.effects.empty() {
+            return;
+        }
+        let step = SineWave::new(70.0)
+            .take_duration(Duration::from_millis(60))
+            .amplify(volume);
+        self.effects.append(step);
     }
 }
@@ -0,0 +1,147 @@
+use crate::maze::is_wall;
+use crate::rng::Rng;
+
+// Post-procesado sobre la rejilla de caracteres. Sólo se quitan paredes, nunca
+// se ponen, así que si `p` y `g` estaban conectados lo siguen estando.
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+// Quita el porcentaje indicado (0.0 a 1.0) de callejones sin salida,
+// abriendo una de sus paredes hacia una celda vecina.
+pub fn remove_dead_ends(maze: &mut [Vec<char>], ratio: f32, rng: &mut Rng) {
+    let (cols, rows) = cell_dimensions(maze);
+
+    let mut dead_ends = Vec::new();
+    for row in 0..rows {
+        for col in 0..cols {
+            if is_dead_end(maze, col, row) {
+                dead_ends.push((col, row));
+            }
+        }
+    }
+    rng.shuffle(&mut dead_ends);
+
+    let count = (dead_ends.len() as f32 * ratio.clamp(0.0, 1.0)).round() as usize;
+    for &(col, row) in dead_ends.iter().take(count) {
+        // Abrir otro callejón ya lo puede haber resuelto
+        if !is_dead_end(maze, col, row) {
+            continue;
+        }
+
+        let mut closed: Vec<(usize, usize)> = DIRECTIONS
+            .iter()
+            .filter_map(|&dir| neighbor(col, row, dir, cols, rows))
+            .filter(|&other| is_floor(maze, other) && !is_open(maze, (col, row), other))
+            .collect();
+        rng.shuffle(&mut closed);
+
+        // Preferir unir dos callejones, así se eliminan ambos de una vez
+        let target = closed
+            .iter()
+            .copied()
+            .find(|&(ncol, nrow)| is_dead_end(maze, ncol, nrow))
+            .or_else(|| closed.first().copied());
+
+        if let Some(target) = target {
+            open_wall(maze, (col, row), target);
+        }
+    }
+}
+
+// Derriba `count` paredes interiores al azar para crear ciclos. Sólo cuentan
+// las que separan dos celdas de piso.
+pub fn add_loops(maze: &mut [Vec<char>], count: usize, rng: &mut Rng) {
+    let (cols, rows) = cell_dimensions(maze);
+
+    let mut walls = Vec::new();
+    for row in 0..rows {
+        for col in 0..cols {
+            for other in [(col + 1, row), (col, row + 1)] {
+                if other.0 < cols && other.1 < rows && separates_floor(maze, (col, row), other) {
+                    walls.push(((col, row), other));
+                }
+            }
+        }
+    }
+    rng.shuffle(&mut walls);
+
+    for &(a, b) in walls.iter().take(count) {
+        open_wall(maze, a, b);
+    }
+}
+
+// Número de celdas de 3 caracteres en cada eje
+fn cell_dimensions(maze: &[Vec<char>]) -> (usize, usize) {
+    let rows = maze.len().saturating_sub(1) / 2;
+    let cols = maze
+        .iter()
+        .map(|line| line.len())
+        .min()
+        .unwrap_or(0)
+        .saturating_sub(1)
+        / 3;
+    (cols, rows)
+}
+
+fn neighbor(
+    col: usize,
+    row: usize,
+    (dx, dy): (isize, isize),
+    cols: usize,
+    rows: usize,
+) -> Option<(usize, usize)> {
+    let ncol = col as isize + dx;
+    let nrow = row as isize + dy;
+    if ncol < 0 || nrow < 0 || ncol >= cols as isize || nrow >= rows as isize {
+        return None;
+    }
+    Some((ncol as usize, nrow as usize))
+}
+
+// Posiciones en la rejilla de caracteres de la pared entre dos celdas vecinas
+fn wall_chars(a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+    let (first, second) = if (a.1, a.0) < (b.1, b.0) { (a, b) } else { (b, a) };
+    if second.1 == first.1 {
+        vec![(first.0 * 3 + 3, first.1 * 2 + 1)]
+    } else {
+        vec![(first.0 * 3 + 1, first.1 * 2 + 2), (first.0 * 3 + 2, first.1 * 2 + 2)]
+    }
+}
+
+fn is_open(maze: &[Vec<char>], a: (usize, usize), b: (usize, usize)) -> bool {
+    wall_chars(a, b).iter().all(|&(x, y)| !is_wall(maze[y][x]))
+}
+
+// Celdas que `CellGrid::to_chars` deja como roca sólida (`++`) no son piso
+fn is_floor(maze: &[Vec<char>], (col, row): (usize, usize)) -> bool {
+    maze[row * 2 + 1][col * 3 + 1] != '+'
+}
+
+fn separates_floor(maze: &[Vec<char>], a: (usize, usize), b: (usize, usize)) -> bool {
+    is_floor(maze, a) && is_floor(maze, b) && !is_open(maze, a, b)
+}
+
+// Nunca se abre hacia la roca, para no dejar muescas en las paredes de las salas
+fn open_wall(maze: &mut [Vec<char>], a: (usize, usize), b: (usize, usize)) {
+    if !is_floor(maze, a) || !is_floor(maze, b) {
+        return;
+    }
+    for (x, y) in wall_chars(a, b) {
+        if is_wall(maze[y][x]) {
+            maze[y][x] = ' ';
+        }
+    }
+}
+
+fn is_dead_end(maze: &[Vec<char>], col: usize, row: usize) -> bool {
+    if !is_floor(maze, (col, row)) {
+        return false;
+    }
+    let (cols, rows) = cell_dimensions(maze);
+    let open = DIRECTIONS
+        .iter()
+        .filter_map(|&dir| neighbor(col, row, dir, cols, rows))
+        .filter(|&other| is_open(maze, (col, row), other))
+        .count();
+    open == 1
+}
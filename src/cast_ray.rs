@@ -1,46 +1,171 @@
-use crate::framebuffer::Framebuffer;
+use nalgebra_glm::Vec2;
 use crate::player::Player;
-use crate::color::Color;
+use crate::doors::{Door, Doors};
+use crate::maze::{is_door, is_walkable};
+
+// Cara de la casilla que golpeó el rayo: `Vertical` es una línea de la
+// rejilla con x constante, `Horizontal` una con y constante.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Vertical,
+    Horizontal,
+}
 
 pub struct Intersect {
     pub distance: f32,
     pub impact: char,
+    pub hit: Vec2,
+    pub side: Side,
+    // Casilla de la rejilla que detuvo el rayo. Ningún llamador la lee todavía,
+    // pero se expone para no tener que volver a deducirla de `hit`.
+    #[allow(dead_code)]
+    pub cell: (usize, usize),
+    // Posición del impacto a lo largo de la cara de la pared, de 0.0 a 1.0
+    pub tex_offset: f32,
 }
 
+// Recorre la rejilla casilla por casilla (DDA) hasta la primera por la que no
+// se pueda caminar, así el punto de impacto y la distancia son exactos. El
+// inicio y la meta son transparentes, igual que para el movimiento; las puertas
+// sólo tapan la parte del panel que sigue cerrada.
 pub fn cast_ray(
-    framebuffer: &mut Framebuffer,
-    maze: &Vec<Vec<char>>,
+    maze: &[Vec<char>],
+    doors: &Doors,
     player: &Player,
     a: f32,
     block_size: usize,
-    draw_line: bool, // Parámetro adicional para controlar el dibujo de la línea
 ) -> Intersect {
-    let mut d = 0.0;
+    let block = block_size as f32;
+    let dir = Vec2::new(a.cos(), a.sin());
 
-    framebuffer.set_current_color(Color::new(255, 221, 221)); // Color de la línea de rayos
+    let mut i = (player.pos.x / block).floor() as isize;
+    let mut j = (player.pos.y / block).floor() as isize;
 
-    loop {
-        let cos = d * a.cos();
-        let sin = d * a.sin();
-        let x = (player.pos.x + cos) as usize;
-        let y = (player.pos.y + sin) as usize;
+    // Distancia que recorre el rayo para cruzar una casilla completa en cada eje
+    let delta_x = if dir.x == 0.0 { f32::INFINITY } else { block / dir.x.abs() };
+    let delta_y = if dir.y == 0.0 { f32::INFINITY } else { block / dir.y.abs() };
 
-        let i = x / block_size;
-        let j = y / block_size;
+    let (step_i, mut side_x) = if dir.x < 0.0 {
+        (-1, (player.pos.x - i as f32 * block) / block * delta_x)
+    } else {
+        (1, ((i + 1) as f32 * block - player.pos.x) / block * delta_x)
+    };
+    let (step_j, mut side_y) = if dir.y < 0.0 {
+        (-1, (player.pos.y - j as f32 * block) / block * delta_y)
+    } else {
+        (1, ((j + 1) as f32 * block - player.pos.y) / block * delta_y)
+    };
+
+    loop {
+        let (distance, side) = if side_x < side_y {
+            let d = side_x;
+            side_x += delta_x;
+            i += step_i;
+            (d, Side::Vertical)
+        } else {
+            let d = side_y;
+            side_y += delta_y;
+            j += step_j;
+            (d, Side::Horizontal)
+        };
 
-        // Si el rayo golpea una pared (cualquier celda que no sea ' '), devolvemos la intersección
-        if maze[j][i] != ' ' {
+        // Fuera de la rejilla: no debería pasar con un laberinto validado
+        if i < 0 || j < 0 || j as usize >= maze.len() || i as usize >= maze[j as usize].len() {
+            let hit = player.pos + dir * distance;
             return Intersect {
-                distance: d,
-                impact: maze[j][i],
+                distance,
+                impact: ' ',
+                hit,
+                side,
+                cell: (i.max(0) as usize, j.max(0) as usize),
+                tex_offset: face_offset(hit, side, dir, block),
             };
         }
 
-        // Dibuja la línea solo si draw_line es true
-        if draw_line {
-            framebuffer.point(x, y, framebuffer.current_color.to_hex());
+        let cell = maze[j as usize][i as usize];
+        let cell_index = (i as usize, j as usize);
+
+        if is_door(cell) {
+            let exit = side_x.min(side_y);
+            let hit = doors
+                .get(cell_index)
+                .and_then(|door| door_hit(player.pos, dir, cell_index, door, block, distance, exit));
+            if let Some((distance, tex_offset, side)) = hit {
+                return Intersect {
+                    distance,
+                    impact: cell,
+                    hit: player.pos + dir * distance,
+                    side,
+                    cell: cell_index,
+                    tex_offset,
+                };
+            }
+            continue;
+        }
+
+        if !is_walkable(cell) {
+            let hit = player.pos + dir * distance;
+            return Intersect {
+                distance,
+                impact: cell,
+                hit,
+                side,
+                cell: cell_index,
+                tex_offset: face_offset(hit, side, dir, block),
+            };
         }
+    }
+}
+
+// Se invierte en dos de las caras para que la textura no salga en espejo
+fn face_offset(hit: Vec2, side: Side, dir: Vec2, block: f32) -> f32 {
+    let along = match side {
+        Side::Vertical => hit.y,
+        Side::Horizontal => hit.x,
+    };
+    let offset = (along / block).fract();
+    if (side == Side::Vertical && dir.x < 0.0) || (side == Side::Horizontal && dir.y > 0.0) {
+        1.0 - offset
+    } else {
+        offset
+    }
+}
 
-        d += 10.0;
+// El panel de la puerta está hundido en el centro de la casilla y se desliza
+// hacia las coordenadas crecientes. Devuelve distancia, desplazamiento en la
+// textura y orientación si el rayo toca la parte que sigue cerrada entre
+// `entry` y `exit`, las distancias a las que entra y sale de la casilla.
+fn door_hit(
+    pos: Vec2,
+    dir: Vec2,
+    (i, j): (usize, usize),
+    door: &Door,
+    block: f32,
+    entry: f32,
+    exit: f32,
+) -> Option<(f32, f32, Side)> {
+    let (plane, origin, speed) = match door.side {
+        Side::Vertical => ((i as f32 + 0.5) * block, pos.x, dir.x),
+        Side::Horizontal => ((j as f32 + 0.5) * block, pos.y, dir.y),
+    };
+    if speed == 0.0 {
+        return None;
     }
+
+    let distance = (plane - origin) / speed;
+    if distance < entry || distance >= exit {
+        return None;
+    }
+
+    let hit = pos + dir * distance;
+    let along = match door.side {
+        Side::Vertical => hit.y,
+        Side::Horizontal => hit.x,
+    };
+    let offset = (along / block).fract();
+    if offset < door.open {
+        return None;
+    }
+
+    Some((distance, offset - door.open, door.side))
 }
@@ -0,0 +1,106 @@
+use nalgebra_glm::Vec2;
+use crate::color::Color;
+use crate::player::touches_item;
+use crate::rng::Rng;
+use crate::solver::Cell;
+use crate::texture::Texture;
+
+// Carácter de las monedas en los archivos de nivel
+pub const COIN: char = '*';
+// Puntos por moneda
+pub const COIN_VALUE: usize = 100;
+
+pub fn coin_color() -> Color {
+    Color::new(255, 200, 40)
+}
+
+// Monedas que quedan en el piso y cuántas había al empezar
+pub struct Collectibles {
+    items: Vec<Cell>,
+    total: usize,
+}
+
+impl Collectibles {
+    // Saca las monedas de la rejilla y deja piso en su lugar
+    pub fn extract(maze: &mut [Vec<char>]) -> Self {
+        let mut items = Vec::new();
+        for (row, line) in maze.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                if *cell == COIN {
+                    items.push((col, row));
+                    *cell = ' ';
+                }
+            }
+        }
+        let total = items.len();
+        Collectibles { items, total }
+    }
+
+    pub fn items(&self) -> &[Cell] {
+        &self.items
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn collected(&self) -> usize {
+        self.total - self.items.len()
+    }
+
+    pub fn score(&self) -> usize {
+        self.collected() * COIN_VALUE
+    }
+
+    // Porcentaje de monedas recogidas; sin monedas cuenta como completo
+    pub fn completion(&self) -> f32 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.collected() as f32 * 100.0 / self.total as f32
+        }
+    }
+
+    // Quita del piso las monedas que toca el jugador en `pos`; el puntaje sale
+    // de cuántas faltan respecto al total
+    pub fn pick_up(&mut self, pos: Vec2, block_size: usize) {
+        self.items.retain(|&cell| !touches_item(pos, cell, block_size));
+    }
+}
+
+// Reparte hasta `count` monedas en celdas vacías distintas, a lo sumo una por celda
+pub fn scatter_coins(maze: &mut [Vec<char>], count: usize, rng: &mut Rng) {
+    let mut floor = Vec::new();
+    for row in (1..maze.len()).step_by(2) {
+        for col in (1..maze[row].len()).step_by(3) {
+            if maze[row][col] == ' ' && maze[row].get(col + 1) == Some(&' ') {
+                floor.push((col, row));
+            }
+        }
+    }
+    rng.shuffle(&mut floor);
+
+    for &(col, row) in floor.iter().take(count) {
+        maze[row][col] = COIN;
+    }
+}
+
+// Moneda dorada con borde más oscuro y un brillo arriba a la izquierda
+pub fn coin_texture() -> Texture {
+    let size = 32;
+    let radius = size as f32 / 2.0;
+    Texture::from_fn(size, size, |x, y| {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance > radius {
+            None
+        } else if distance > radius - 3.0 {
+            Some(Color::new(190, 130, 20))
+        } else if (dx + 5.0).abs() < 2.5 && (dy + 5.0).abs() < 2.5 {
+            Some(Color::new(255, 250, 200))
+        } else {
+            Some(coin_color())
+        }
+    })
+}
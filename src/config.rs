@@ -0,0 +1,148 @@
+use std::env;
+use crate::keys::KEY_ORDER;
+use crate::solver::{strategy_by_name, Strategy, STRATEGY_NAMES};
+
+// Opciones de línea de comandos, p. ej. `cargo run -- --algorithm prim --seed 42`
+pub struct Config {
+    // Archivo de laberinto a cargar en lugar de generar uno
+    pub maze: Option<String>,
+    pub algorithm: Option<String>,
+    pub seed: Option<u64>,
+    // Fracción de callejones sin salida a eliminar (0.0 a 1.0)
+    pub braid: f32,
+    // Paredes extra a derribar para crear ciclos
+    pub loops: usize,
+    // Estrategia con la que se calcula la pista (tecla H)
+    pub hint_solver: Strategy,
+    // Campo de visión en grados
+    pub fov: f32,
+    // Parejas de llave y puerta con cerradura en el laberinto generado
+    pub keys: usize,
+    // Monedas repartidas en el laberinto generado
+    pub coins: usize,
+    // Enemigos en el laberinto generado
+    pub enemies: usize,
+    // Balanceo de la cabeza al caminar en la vista 3D
+    pub head_bob: bool,
+}
+
+impl Config {
+    pub fn from_args() -> Result<Self, String> {
+        let mut config = Config {
+            maze: None,
+            algorithm: None,
+            seed: None,
+            braid: 0.0,
+            loops: 0,
+            hint_solver: Strategy::AStar,
+            fov: 60.0,
+            keys: 0,
+            coins: 10,
+            enemies: 2,
+            head_bob: true,
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--maze" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| String::from("--maze requires a value"))?;
+                    config.maze = Some(path);
+                }
+                "--algorithm" => {
+                    let name = args
+                        .next()
+                        .ok_or_else(|| String::from("--algorithm requires a value"))?;
+                    config.algorithm = Some(name);
+                }
+                "--seed" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--seed requires a value"))?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed: {}", value))?;
+                    config.seed = Some(seed);
+                }
+                "--braid" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--braid requires a value"))?;
+                    let braid: f32 = value
+                        .parse()
+                        .map_err(|_| format!("Invalid braid ratio: {}", value))?;
+                    if !(0.0..=1.0).contains(&braid) {
+                        return Err(format!("Braid ratio must be between 0 and 1: {}", value));
+                    }
+                    config.braid = braid;
+                }
+                "--loops" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--loops requires a value"))?;
+                    config.loops = value
+                        .parse()
+                        .map_err(|_| format!("Invalid loop count: {}", value))?;
+                }
+                "--hint-solver" => {
+                    let name = args
+                        .next()
+                        .ok_or_else(|| String::from("--hint-solver requires a value"))?;
+                    config.hint_solver = strategy_by_name(&name).ok_or_else(|| {
+                        format!(
+                            "Unknown solver '{}', expected one of: {}",
+                            name,
+                            STRATEGY_NAMES.join(", ")
+                        )
+                    })?;
+                }
+                "--fov" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--fov requires a value"))?;
+                    let fov: f32 = value
+                        .parse()
+                        .map_err(|_| format!("Invalid field of view: {}", value))?;
+                    if !(30.0..=120.0).contains(&fov) {
+                        return Err(format!("Field of view must be between 30 and 120 degrees: {}", value));
+                    }
+                    config.fov = fov;
+                }
+                "--keys" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--keys requires a value"))?;
+                    let keys: usize = value
+                        .parse()
+                        .map_err(|_| format!("Invalid key count: {}", value))?;
+                    if keys > KEY_ORDER.len() {
+                        return Err(format!("At most {} keys are supported: {}", KEY_ORDER.len(), value));
+                    }
+                    config.keys = keys;
+                }
+                "--coins" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--coins requires a value"))?;
+                    config.coins = value
+                        .parse()
+                        .map_err(|_| format!("Invalid coin count: {}", value))?;
+                }
+                "--enemies" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| String::from("--enemies requires a value"))?;
+                    config.enemies = value
+                        .parse()
+                        .map_err(|_| format!("Invalid enemy count: {}", value))?;
+                }
+                "--no-head-bob" => config.head_bob = false,
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+
+        Ok(config)
+    }
+}
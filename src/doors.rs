@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use nalgebra_glm::Vec2;
+use crate::cast_ray::Side;
+use crate::color::Color;
+use crate::maze::{is_door, is_passable, key_for_door};
+use crate::player::touches_cell;
+use crate::solver::Cell;
+use crate::texture::Texture;
+
+// Fracción de la puerta que se abre o se cierra por segundo
+const DOOR_SPEED: f32 = 1.5;
+// A partir de aquí la puerta deja pasar al jugador
+const PASSABLE_OPEN: f32 = 0.9;
+
+// Una puerta es un panel en el centro de su casilla que se desliza hacia un
+// lado. `side` es la orientación del panel, con el mismo significado que en
+// `cast_ray`: `Vertical` es un panel con x constante.
+pub struct Door {
+    pub side: Side,
+    pub open: f32,
+    opening: bool,
+    // Llave que hace falta para abrirla; se olvida una vez abierta
+    lock: Option<char>,
+}
+
+pub struct Doors {
+    doors: HashMap<Cell, Door>,
+}
+
+// El panel queda perpendicular al paso: si se cruza de izquierda a derecha, el
+// panel es vertical. Devuelve None si la puerta no separa dos casillas.
+pub fn door_side(maze: &[Vec<char>], (col, row): Cell) -> Option<Side> {
+    let passable = |col: usize, row: usize| {
+        maze.get(row)
+            .and_then(|line| line.get(col))
+            .is_some_and(|&cell| is_passable(cell))
+    };
+
+    if col > 0 && passable(col - 1, row) && passable(col + 1, row) {
+        Some(Side::Vertical)
+    } else if row > 0 && passable(col, row - 1) && passable(col, row + 1) {
+        Some(Side::Horizontal)
+    } else {
+        None
+    }
+}
+
+impl Doors {
+    // Todas las puertas del laberinto empiezan cerradas
+    pub fn from_maze(maze: &[Vec<char>]) -> Self {
+        let mut doors = HashMap::new();
+        for (row, line) in maze.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                if !is_door(cell) {
+                    continue;
+                }
+                if let Some(side) = door_side(maze, (col, row)) {
+                    let lock = key_for_door(cell);
+                    doors.insert((col, row), Door { side, open: 0.0, opening: false, lock });
+                }
+            }
+        }
+        Doors { doors }
+    }
+
+    pub fn get(&self, cell: Cell) -> Option<&Door> {
+        self.doors.get(&cell)
+    }
+
+    // Fracción abierta, de 0.0 (cerrada) a 1.0; las casillas sin puerta cuentan como abiertas
+    pub fn open_amount(&self, cell: Cell) -> f32 {
+        self.doors.get(&cell).map(|door| door.open).unwrap_or(1.0)
+    }
+
+    pub fn is_passable(&self, cell: Cell) -> bool {
+        self.open_amount(cell) >= PASSABLE_OPEN
+    }
+
+    // Abre o cierra la puerta que está justo delante de `pos`, mirando hacia
+    // `angle`, junto con las casillas de puerta pegadas a ella. Las que tienen
+    // cerradura sólo se abren si la llave está entre las de `held`.
+    pub fn toggle_in_front(&mut self, pos: Vec2, angle: f32, block_size: usize, held: &[char]) {
+        let block = block_size as f32;
+        let forward = Vec2::new(angle.cos(), angle.sin());
+
+        // Se prueba a media casilla y a una casilla de distancia
+        let target = [0.5, 1.0]
+            .iter()
+            .map(|reach| pos + forward * (reach * block))
+            .filter(|point| point.x >= 0.0 && point.y >= 0.0)
+            .map(|point| ((point.x / block) as usize, (point.y / block) as usize))
+            .find(|cell| self.doors.contains_key(cell));
+
+        let Some(target) = target else {
+            return;
+        };
+
+        let opening = !self.doors[&target].opening;
+        let group = self.connected(target);
+
+        // No se cierra una puerta con el jugador dentro, ni siquiera en parte
+        if !opening && group.iter().any(|&cell| touches_cell(pos, cell, block_size)) {
+            return;
+        }
+
+        let locked = group
+            .iter()
+            .filter_map(|cell| self.doors[cell].lock)
+            .any(|key| !held.contains(&key));
+        if locked {
+            return;
+        }
+
+        for cell in group {
+            if let Some(door) = self.doors.get_mut(&cell) {
+                door.opening = opening;
+                door.lock = None;
+            }
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for door in self.doors.values_mut() {
+            let target = if door.opening { 1.0 } else { 0.0 };
+            let step = DOOR_SPEED * dt;
+            door.open = if door.open < target {
+                (door.open + step).min(target)
+            } else {
+                (door.open - step).max(target)
+            };
+        }
+    }
+
+    // Casillas de puerta vecinas entre sí, como las dos `DD` de una pared horizontal
+    fn connected(&self, start: Cell) -> Vec<Cell> {
+        let mut group = vec![start];
+        let mut index = 0;
+        while index < group.len() {
+            let (col, row) = group[index];
+            index += 1;
+            let neighbors = [
+                (col.wrapping_sub(1), row),
+                (col + 1, row),
+                (col, row.wrapping_sub(1)),
+                (col, row + 1),
+            ];
+            for neighbor in neighbors {
+                if self.doors.contains_key(&neighbor) && !group.contains(&neighbor) {
+                    group.push(neighbor);
+                }
+            }
+        }
+        group
+    }
+}
+
+// Textura por defecto de las puertas: tablones de madera con un marco oscuro
+pub fn door_texture() -> Texture {
+    framed_door_texture(Color::new(60, 40, 20))
+}
+
+// Las puertas con cerradura llevan el marco del color de su llave
+pub fn locked_door_texture(color: Color) -> Texture {
+    framed_door_texture(color)
+}
+
+fn framed_door_texture(frame_color: Color) -> Texture {
+    let size = 64;
+    Texture::from_fn(size, size, |x, y| {
+        let frame = x < 4 || y < 4 || x >= size - 4 || y >= size - 4;
+        let plank_edge = x % 16 == 0;
+        let handle = (48..54).contains(&x) && (28..36).contains(&y);
+
+        let color = if frame {
+            frame_color
+        } else if handle {
+            Color::new(220, 190, 60)
+        } else if plank_edge {
+            Color::new(90, 60, 30)
+        } else {
+            let grain = ((x * 7 + y * 3) % 11) as u8;
+            Color::new(140 + grain, 95 + grain, 50)
+        };
+        Some(color)
+    })
+}
@@ -0,0 +1,235 @@
+use nalgebra_glm::Vec2;
+use crate::cast_ray::cast_ray;
+use crate::color::Color;
+use crate::doors::Doors;
+use crate::maze::is_door;
+use crate::player::Player;
+use crate::rng::Rng;
+use crate::solver::{find_cell, shortest_path, solve, Cell, Strategy};
+use crate::texture::Texture;
+
+// Carácter de los enemigos en los archivos de nivel
+pub const ENEMY: char = 'e';
+
+// Velocidades en casillas por segundo
+const PATROL_SPEED: f32 = 3.0;
+const CHASE_SPEED: f32 = 6.0;
+// Más lejos que esto no ven al jugador, aunque no haya paredes en medio
+const SIGHT_RANGE: f32 = 10.0;
+// Distancia, en casillas, a la que atrapan al jugador
+const CONTACT_RANGE: f32 = 0.6;
+// En los laberintos generados no aparecen a menos de estas casillas de `p`
+const MIN_SPAWN_DISTANCE: usize = 15;
+
+pub struct Enemy {
+    pub pos: Vec2,
+    spawn: Cell,
+    // Casillas que le quedan por recorrer, la siguiente primero
+    route: Vec<Cell>,
+    pub chasing: bool,
+}
+
+// Los enemigos patrullan entre casillas al azar hasta que ven al jugador;
+// entonces lo persiguen por la rejilla hasta alcanzarlo o perderlo de vista.
+pub struct Enemies {
+    enemies: Vec<Enemy>,
+    floor: Vec<Cell>,
+    rng: Rng,
+}
+
+fn cell_center((col, row): Cell, block: f32) -> Vec2 {
+    Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * block
+}
+
+fn cell_of(pos: Vec2, block: f32) -> Cell {
+    ((pos.x / block) as usize, (pos.y / block) as usize)
+}
+
+impl Enemies {
+    // Saca los enemigos de la rejilla y deja piso en su lugar
+    pub fn extract(maze: &mut [Vec<char>], block_size: usize, seed: u64) -> Self {
+        let block = block_size as f32;
+        let mut enemies = Vec::new();
+        let mut floor = Vec::new();
+
+        for (row, line) in maze.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                if *cell == ENEMY {
+                    enemies.push(Enemy {
+                        pos: cell_center((col, row), block),
+                        spawn: (col, row),
+                        route: Vec::new(),
+                        chasing: false,
+                    });
+                    *cell = ' ';
+                }
+                if *cell == ' ' {
+                    floor.push((col, row));
+                }
+            }
+        }
+
+        Enemies { enemies, floor, rng: Rng::new(seed) }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Enemy> {
+        self.enemies.iter()
+    }
+
+    // Vuelven a su casilla inicial, p. ej. después de atrapar al jugador
+    pub fn reset(&mut self, block_size: usize) {
+        for enemy in &mut self.enemies {
+            enemy.pos = cell_center(enemy.spawn, block_size as f32);
+            enemy.route.clear();
+            enemy.chasing = false;
+        }
+    }
+
+    // Mueve a todos los enemigos `dt` segundos. Devuelve true si alguno
+    // alcanzó al jugador.
+    pub fn update(
+        &mut self,
+        maze: &[Vec<char>],
+        doors: &Doors,
+        player: &Player,
+        block_size: usize,
+        dt: f32,
+    ) -> bool {
+        let block = block_size as f32;
+        let player_cell = cell_of(player.pos, block);
+        let open = |cell: Cell| !is_door(maze[cell.1][cell.0]) || doors.is_passable(cell);
+        let mut caught = false;
+
+        for enemy in &mut self.enemies {
+            let cell = cell_of(enemy.pos, block);
+
+            if can_see(maze, doors, enemy.pos, player.pos, block_size) {
+                // Se recalcula sólo cuando el jugador cambia de casilla
+                if !enemy.chasing || enemy.route.last() != Some(&player_cell) {
+                    if let Some(path) = shortest_path(maze, cell, player_cell, open) {
+                        enemy.route = path[1..].to_vec();
+                    }
+                }
+                enemy.chasing = true;
+            }
+
+            // El jugador cerró una puerta en el camino
+            if enemy.route.iter().any(|&next| !open(next)) {
+                enemy.route.clear();
+            }
+
+            // Al perder el rastro o terminar la ronda, otra casilla al azar
+            if enemy.route.is_empty() {
+                enemy.chasing = false;
+                if !self.floor.is_empty() {
+                    let target = self.floor[self.rng.range(self.floor.len())];
+                    if let Some(path) = shortest_path(maze, cell, target, open) {
+                        enemy.route = path[1..].to_vec();
+                    }
+                }
+            }
+
+            let speed = if enemy.chasing { CHASE_SPEED } else { PATROL_SPEED };
+            let mut step = speed * block * dt;
+            while step > 0.0 {
+                let Some(&next) = enemy.route.first() else {
+                    break;
+                };
+                let target = cell_center(next, block);
+                let remaining = (target - enemy.pos).norm();
+                if remaining <= step {
+                    enemy.pos = target;
+                    enemy.route.remove(0);
+                    step -= remaining;
+                } else {
+                    enemy.pos += (target - enemy.pos) / remaining * step;
+                    step = 0.0;
+                }
+            }
+
+            if (enemy.pos - player.pos).norm() < CONTACT_RANGE * block {
+                caught = true;
+            }
+        }
+
+        caught
+    }
+}
+
+// Hay línea de visión si el primer obstáculo del rayo hacia el jugador queda
+// más lejos que él
+fn can_see(maze: &[Vec<char>], doors: &Doors, from: Vec2, to: Vec2, block_size: usize) -> bool {
+    let offset = to - from;
+    let distance = offset.norm();
+    if distance > SIGHT_RANGE * block_size as f32 {
+        return false;
+    }
+    if distance < 0.001 {
+        return true;
+    }
+
+    let angle = offset.y.atan2(offset.x);
+    let eye = Player::new(from.x, from.y, angle, 0.0);
+    cast_ray(maze, doors, &eye, angle, block_size).distance >= distance
+}
+
+// Pone hasta `count` enemigos en celdas vacías lejos de `p`
+pub fn spawn_enemies(maze: &mut [Vec<char>], count: usize, rng: &mut Rng) {
+    let (Some(start), Some(goal)) = (find_cell(maze, 'p'), find_cell(maze, 'g')) else {
+        return;
+    };
+
+    let mut floor = Vec::new();
+    for row in (1..maze.len()).step_by(2) {
+        for col in (1..maze[row].len()).step_by(3) {
+            if maze[row][col] != ' ' || (col, row) == goal {
+                continue;
+            }
+            let far = solve(maze, start, (col, row), Strategy::Bfs)
+                .is_some_and(|path| path.len() > MIN_SPAWN_DISTANCE);
+            if far {
+                floor.push((col, row));
+            }
+        }
+    }
+    rng.shuffle(&mut floor);
+
+    for &(col, row) in floor.iter().take(count) {
+        maze[row][col] = ENEMY;
+    }
+}
+
+pub fn enemy_color() -> Color {
+    Color::new(220, 30, 60)
+}
+
+// Fantasma rojo: cabeza redonda, cuerpo recto con el borde de abajo en picos
+pub fn enemy_texture() -> Texture {
+    let size = 64;
+    let radius = size as f32 / 2.0;
+    Texture::from_fn(size, size, |x, y| {
+        let (fx, fy) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        let feet_top = size as f32 - 8.0;
+        let in_head = fy < radius && (fx - radius).powi(2) + (fy - radius).powi(2) < radius * radius;
+        let in_body = fy >= radius && fy < feet_top;
+        // Picos de 16 píxeles de ancho y 8 de alto
+        let tooth = 8.0 - ((x % 16) as f32 - 8.0).abs();
+        let in_feet = fy >= feet_top && fy - feet_top < tooth;
+        if !(in_head || in_body || in_feet) {
+            return None;
+        }
+
+        for eye_x in [22.0, 42.0] {
+            let dx = fx - eye_x;
+            let dy = fy - 26.0;
+            if dx * dx + dy * dy < 16.0 {
+                return Some(Color::new(20, 20, 60));
+            }
+            if dx * dx + dy * dy < 49.0 {
+                return Some(Color::new(255, 255, 255));
+            }
+        }
+        Some(enemy_color())
+    })
+}
@@ -1,61 +1,149 @@
 use minifb::{Window, Key, MouseMode};
 use nalgebra_glm::Vec2;
 use std::f32::consts::PI;
-use crate::player::Player;
+use crate::player::{touches_cell, Player, MAX_PITCH, PLAYER_RADIUS, SPRINT_FACTOR};
 use crate::audio_player::AudioPlayer; 
+use crate::doors::Doors;
+use crate::maze::{is_door, is_walkable};
+use crate::solver::Cell;
+use crate::world::World;
 
+// Estado de la entrada que se guarda entre ticks
+#[derive(Default)]
+pub struct InputState {
+    pub last_mouse_pos: Option<(f32, f32)>,
+    // Un tick de simulación puede repetirse en el mismo cuadro, así que la
+    // pulsación de E se detecta a mano y no con `is_key_pressed`
+    use_was_down: bool,
+}
+
+// Avanza al jugador `dt` segundos según las teclas y el mouse
 pub fn process_events(
     window: &Window,
     player: &mut Player,
-    maze: &Vec<Vec<char>>,
+    world: &mut World,
     block_size: usize,
-    last_mouse_pos: &mut Option<(f32, f32)>,
+    input: &mut InputState,
     audio_player: &AudioPlayer,  
+    dt: f32,
 ) {
-    const MOVE_SPEED: f32 = 10.0;
-    const ROTATION_SPEED: f32 = PI / 10.0;
+    // Casillas por segundo
+    const MOVE_SPEED: f32 = 8.0;
+    // Radianes por segundo
+    const ROTATION_SPEED: f32 = PI;
     const MOUSE_SENSITIVITY: f32 = 0.015;
+    // Agachado se avanza más despacio
+    const CROUCH_MOVE_FACTOR: f32 = 0.5;
+    const FOOTSTEP_VOLUME: f32 = 0.15;
 
 
-    if let Some((mouse_x, _)) = window.get_mouse_pos(MouseMode::Discard) {
-        if let Some((last_x, _)) = last_mouse_pos {
-            let dx = mouse_x - *last_x;
+    if let Some((mouse_x, mouse_y)) = window.get_mouse_pos(MouseMode::Discard) {
+        if let Some((last_x, last_y)) = input.last_mouse_pos {
+            let dx = mouse_x - last_x;
+            let dy = mouse_y - last_y;
             player.a += dx * MOUSE_SENSITIVITY;
+            // Subir el mouse levanta la vista
+            player.pitch = (player.pitch - dy * MOUSE_SENSITIVITY).clamp(-MAX_PITCH, MAX_PITCH);
         }
-        *last_mouse_pos = Some((mouse_x, 0.0));
+        input.last_mouse_pos = Some((mouse_x, mouse_y));
+    }
+
+    // Espacio salta; Ctrl izquierdo agacha mientras se mantiene
+    player.update_height(window.is_key_down(Key::Space), window.is_key_down(Key::LeftCtrl), dt);
+
+    if window.is_key_down(Key::Left) {
+        player.a -= ROTATION_SPEED * dt;
     }
+    if window.is_key_down(Key::Right) {
+        player.a += ROTATION_SPEED * dt;
+    }
+
+    // E abre o cierra la puerta que está enfrente
+    let use_is_down = window.is_key_down(Key::E);
+    if use_is_down && !input.use_was_down {
+        world.doors.toggle_in_front(player.pos, player.a, block_size, world.keys.held());
+    }
+    input.use_was_down = use_is_down;
 
-    let mut new_pos = player.pos.clone();
+    // Shift izquierdo corre mientras quede resistencia
+    let sprinting = window.is_key_down(Key::LeftShift) && player.can_sprint();
+    let speed_factor = if sprinting { SPRINT_FACTOR } else { 1.0 - (1.0 - CROUCH_MOVE_FACTOR) * player.crouch };
+
+    let step = MOVE_SPEED * block_size as f32 * dt * speed_factor;
+    let old_pos = player.pos;
+    let mut new_pos = player.pos;
 
     if window.is_key_down(Key::W) {
-        new_pos.x += player.a.cos() * MOVE_SPEED;
-        new_pos.y += player.a.sin() * MOVE_SPEED;
+        new_pos.x += player.a.cos() * step;
+        new_pos.y += player.a.sin() * step;
     }
     if window.is_key_down(Key::S) {
-        new_pos.x -= player.a.cos() * MOVE_SPEED;
-        new_pos.y -= player.a.sin() * MOVE_SPEED;
+        new_pos.x -= player.a.cos() * step;
+        new_pos.y -= player.a.sin() * step;
     }
 
     if window.is_key_down(Key::D) {
-        new_pos.x -= player.a.sin() * MOVE_SPEED;  
-        new_pos.y += player.a.cos() * MOVE_SPEED;
+        new_pos.x -= player.a.sin() * step;  
+        new_pos.y += player.a.cos() * step;
     }
     if window.is_key_down(Key::A) {
-        new_pos.x += player.a.sin() * MOVE_SPEED * 0.75;  
-        new_pos.y -= player.a.cos() * MOVE_SPEED * 0.75;
+        new_pos.x += player.a.sin() * step * 0.75;  
+        new_pos.y -= player.a.cos() * step * 0.75;
     }
 
-    let new_i = (new_pos.x / block_size as f32) as usize;
-    let new_j = (new_pos.y / block_size as f32) as usize;
-
+    // Cada eje por separado: si el paso en x choca, todavía se puede avanzar
+    // en y, y así el jugador se desliza por la pared
+    let moved_x = Vec2::new(new_pos.x, player.pos.y);
+    if !collides(&world.maze, &world.doors, moved_x, block_size) {
+        player.pos.x = moved_x.x;
+    }
+    let moved_y = Vec2::new(player.pos.x, new_pos.y);
+    if !collides(&world.maze, &world.doors, moved_y, block_size) {
+        player.pos.y = moved_y.y;
+    }
 
-    if maze[new_j][new_i] == ' ' || maze[new_j][new_i] == 'p' || maze[new_j][new_i] == 'g' {
-        player.pos = new_pos;
+    // Empujar una pared no gasta resistencia: cuenta lo que se avanzó de verdad
+    let walked = (player.pos - old_pos).norm() / block_size as f32;
+    player.update_stamina(sprinting && walked > 0.0, dt);
+    if player.walk(walked, dt) {
+        audio_player.play_footstep(FOOTSTEP_VOLUME);
     }
 
+    let new_i = (player.pos.x / block_size as f32) as usize;
+    let new_j = (player.pos.y / block_size as f32) as usize;
+    // Se recoge todo lo que toque el círculo del jugador, no sólo lo que está bajo el centro
+    world.keys.pick_up(player.pos, block_size);
+    world.coins.pick_up(player.pos, block_size);
 
-    if maze[new_j][new_i] == 'g' {
+    if world.maze[new_j][new_i] == 'g' {
         audio_player.play_sound_effect("win.wav", 2.0);
     }
 }
 
+// True si el círculo del jugador en `pos` toca alguna casilla por la que no
+// se puede pasar; fuera de la rejilla cuenta como pared
+fn collides(maze: &[Vec<char>], doors: &Doors, pos: Vec2, block_size: usize) -> bool {
+    let block = block_size as f32;
+    let radius = PLAYER_RADIUS * block;
+    let cells = |center: f32| {
+        let first = ((center - radius).max(0.0) / block) as usize;
+        let last = ((center + radius).max(0.0) / block) as usize;
+        first..=last
+    };
+
+    for row in cells(pos.y) {
+        for col in cells(pos.x) {
+            if !is_open(maze, doors, (col, row)) && touches_cell(pos, (col, row), block_size) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn is_open(maze: &[Vec<char>], doors: &Doors, (col, row): Cell) -> bool {
+    match maze.get(row).and_then(|line| line.get(col)) {
+        Some(&cell) => is_walkable(cell) || (is_door(cell) && doors.is_passable((col, row))),
+        None => false,
+    }
+}
@@ -0,0 +1,82 @@
+use crate::framebuffer::Framebuffer;
+
+// Fuente de 5x5 píxeles. Sólo mayúsculas: las minúsculas se dibujan igual.
+pub fn glyph(ch: char) -> [&'static str; 5] {
+    match ch.to_ascii_uppercase() {
+        '0' => [" ### ", "#   #", "#   #", "#   #", " ### "],
+        '1' => ["  #  ", " ##  ", "  #  ", "  #  ", " ### "],
+        '2' => [" ### ", "#   #", "  ## ", " #   ", "#####"],
+        '3' => [" ### ", "#   #", "  ## ", "#   #", " ### "],
+        '4' => ["#   #", "#   #", "#####", "    #", "    #"],
+        '5' => ["#####", "#    ", "#### ", "    #", "#### "],
+        '6' => [" ### ", "#    ", "#### ", "#   #", " ### "],
+        '7' => ["#####", "    #", "   # ", "  #  ", " #   "],
+        '8' => [" ### ", "#   #", " ### ", "#   #", " ### "],
+        '9' => [" ### ", "#   #", " ####", "    #", " ### "],
+        'A' => [" ### ", "#   #", "#####", "#   #", "#   #"],
+        'B' => ["#### ", "#   #", "#### ", "#   #", "#### "],
+        'C' => [" ####", "#    ", "#    ", "#    ", " ####"],
+        'D' => ["#### ", "#   #", "#   #", "#   #", "#### "],
+        'E' => ["#####", "#    ", "#### ", "#    ", "#####"],
+        'F' => ["#####", "#    ", "#####", "#    ", "#    "],
+        'G' => [" ####", "#    ", "#  ##", "#   #", " ### "],
+        'H' => ["#   #", "#   #", "#####", "#   #", "#   #"],
+        'I' => [" ### ", "  #  ", "  #  ", "  #  ", " ### "],
+        'J' => ["  ###", "   # ", "   # ", "#  # ", " ##  "],
+        'K' => ["#   #", "#  # ", "###  ", "#  # ", "#   #"],
+        'L' => ["#    ", "#    ", "#    ", "#    ", "#####"],
+        'M' => ["#   #", "## ##", "# # #", "#   #", "#   #"],
+        'N' => ["#   #", "##  #", "# # #", "#  ##", "#   #"],
+        'O' => [" ### ", "#   #", "#   #", "#   #", " ### "],
+        'P' => ["#### ", "#   #", "#### ", "#    ", "#    "],
+        'Q' => [" ### ", "#   #", "# # #", "#  # ", " ## #"],
+        'R' => ["#### ", "#   #", "#### ", "#  # ", "#   #"],
+        'S' => [" ####", "#    ", " ### ", "    #", "#### "],
+        'T' => ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
+        'U' => ["#   #", "#   #", "#   #", "#   #", " ### "],
+        'V' => ["#   #", "#   #", "#   #", " # # ", "  #  "],
+        'W' => ["#   #", "#   #", "# # #", "## ##", "#   #"],
+        'X' => ["#   #", " # # ", "  #  ", " # # ", "#   #"],
+        'Y' => ["#   #", " # # ", "  #  ", "  #  ", "  #  "],
+        'Z' => ["#####", "   # ", "  #  ", " #   ", "#####"],
+        '.' => ["     ", "     ", "     ", "  ## ", "  ## "],
+        ':' => ["     ", "  #  ", "     ", "  #  ", "     "],
+        '-' => ["     ", "     ", " ### ", "     ", "     "],
+        '_' => ["     ", "     ", "     ", "     ", "#####"],
+        '/' => ["    #", "   # ", "  #  ", " #   ", "#    "],
+        '%' => ["##  #", "## # ", "  #  ", " # ##", "#  ##"],
+        '?' => [" ### ", "#   #", "  ## ", "     ", "  #  "],
+        '!' => ["  #  ", "  #  ", "  #  ", "     ", "  #  "],
+        '>' => [" #   ", "  #  ", "   # ", "  #  ", " #   "],
+        '<' => ["   # ", "  #  ", " #   ", "  #  ", "   # "],
+        '(' => ["   # ", "  #  ", "  #  ", "  #  ", "   # "],
+        ')' => [" #   ", "  #  ", "  #  ", "  #  ", " #   "],
+        '+' => ["     ", "  #  ", " ### ", "  #  ", "     "],
+        '\'' => ["  #  ", "  #  ", "     ", "     ", "     "],
+        _ => ["     ", "     ", "     ", "     ", "     "],
+    }
+}
+
+pub fn draw_glyph(framebuffer: &mut Framebuffer, x: usize, y: usize, ch: char, scale: usize) {
+    for (row, line) in glyph(ch).iter().enumerate() {
+        for (col, pixel) in line.chars().enumerate() {
+            if pixel == '#' {
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        framebuffer.point(x + col * scale + dx, y + row * scale + dy, framebuffer.current_color.to_hex());
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn draw_text(framebuffer: &mut Framebuffer, x: usize, y: usize, text: &str, scale: usize) {
+    for (i, ch) in text.chars().enumerate() {
+        draw_glyph(framebuffer, x + i * (6 * scale), y, ch, scale);
+    }
+}
+
+pub fn text_width(text: &str, scale: usize) -> usize {
+    text.chars().count() * 6 * scale
+}
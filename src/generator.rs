@@ -0,0 +1,633 @@
+use crate::rng::Rng;
+
+// Laberinto a nivel de celdas: cada celda sabe si tiene paso hacia el este y
+// hacia el sur. Todos los algoritmos tallan sobre esta rejilla y luego se
+// convierte al formato de caracteres que entiende el resto del juego.
+pub struct CellGrid {
+    pub width: usize,
+    pub height: usize,
+    east: Vec<bool>,
+    south: Vec<bool>,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+}
+
+impl CellGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        CellGrid {
+            width,
+            height,
+            east: vec![false; width * height],
+            south: vec![false; width * height],
+            start: (0, 0),
+            goal: (width - 1, height - 1),
+        }
+    }
+
+    fn index(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+
+    // Abre el paso entre dos celdas adyacentes
+    pub fn carve(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let (first, second) = if (a.1, a.0) < (b.1, b.0) { (a, b) } else { (b, a) };
+        let i = self.index(first);
+        if second.0 == first.0 + 1 && second.1 == first.1 {
+            self.east[i] = true;
+        } else if second.1 == first.1 + 1 && second.0 == first.0 {
+            self.south[i] = true;
+        }
+    }
+
+    pub fn is_open(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        let (first, second) = if (a.1, a.0) < (b.1, b.0) { (a, b) } else { (b, a) };
+        let i = self.index(first);
+        if second.0 == first.0 + 1 && second.1 == first.1 {
+            self.east[i]
+        } else if second.1 == first.1 + 1 && second.0 == first.0 {
+            self.south[i]
+        } else {
+            false
+        }
+    }
+
+    // Vecinos dentro de la rejilla, sin importar si hay pared o no
+    pub fn neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(4);
+        if x > 0 {
+            result.push((x - 1, y));
+        }
+        if y + 1 < self.height {
+            result.push((x, y + 1));
+        }
+        if x + 1 < self.width {
+            result.push((x + 1, y));
+        }
+        if y > 0 {
+            result.push((x, y - 1));
+        }
+        result
+    }
+
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        let mut cells = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                cells.push((x, y));
+            }
+        }
+        cells
+    }
+
+    // Mismo formato que maze.py: `+--` en filas pares, `|  ` en impares
+    pub fn to_chars(&self) -> Vec<Vec<char>> {
+        let mut maze = Vec::with_capacity(self.height * 2 + 1);
+        for y in 0..=self.height {
+            let mut line = Vec::with_capacity(self.width * 3 + 1);
+            for x in 0..self.width {
+                line.push('+');
+                let open = y > 0 && y < self.height && self.is_open((x, y - 1), (x, y));
+                let segment = if open { ' ' } else { '-' };
+                line.push(segment);
+                line.push(segment);
+            }
+            line.push('+');
+            maze.push(line);
+
+            if y == self.height {
+                break;
+            }
+
+            let mut line = Vec::with_capacity(self.width * 3 + 1);
+            for x in 0..=self.width {
+                let open = x > 0 && x < self.width && self.is_open((x - 1, y), (x, y));
+                line.push(if open { ' ' } else { '|' });
+                if x < self.width {
+                    line.push(' ');
+                    line.push(' ');
+                }
+            }
+            maze.push(line);
+        }
+
+        // Celdas sin ningún paso quedan como roca sólida
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.neighbors((x, y)).iter().all(|&n| !self.is_open((x, y), n)) {
+                    maze[y * 2 + 1][x * 3 + 1] = '+';
+                    maze[y * 2 + 1][x * 3 + 2] = '+';
+                }
+            }
+        }
+
+        // Postes con las cuatro paredes abiertas estorban dentro de las salas
+        for y in 1..self.height {
+            for x in 1..self.width {
+                let (row, col) = (y * 2, x * 3);
+                if maze[row - 1][col] == ' '
+                    && maze[row + 1][col] == ' '
+                    && maze[row][col - 1] == ' '
+                    && maze[row][col + 1] == ' '
+                {
+                    maze[row][col] = ' ';
+                }
+            }
+        }
+
+        maze[self.start.1 * 2 + 1][self.start.0 * 3 + 1] = 'p';
+        maze[self.goal.1 * 2 + 1][self.goal.0 * 3 + 2] = 'g';
+
+        maze
+    }
+}
+
+pub trait MazeGenerator {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng);
+
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Vec<Vec<char>> {
+        let mut rng = Rng::new(seed);
+        let mut grid = CellGrid::new(width, height);
+        self.carve(&mut grid, &mut rng);
+        grid.to_chars()
+    }
+}
+
+pub const GENERATOR_NAMES: [&str; 10] = [
+    "backtracker",
+    "prim",
+    "kruskal",
+    "wilson",
+    "aldous-broder",
+    "eller",
+    "sidewinder",
+    "binary-tree",
+    "growing-tree",
+    "rooms",
+];
+
+pub fn generator_by_name(name: &str) -> Option<Box<dyn MazeGenerator>> {
+    match name {
+        "backtracker" => Some(Box::new(RecursiveBacktracker)),
+        "prim" => Some(Box::new(Prim)),
+        "kruskal" => Some(Box::new(Kruskal)),
+        "wilson" => Some(Box::new(Wilson)),
+        "aldous-broder" => Some(Box::new(AldousBroder)),
+        "eller" => Some(Box::new(Eller)),
+        "sidewinder" => Some(Box::new(Sidewinder)),
+        "binary-tree" => Some(Box::new(BinaryTree)),
+        "growing-tree" => Some(Box::new(GrowingTree { newest_percent: 75 })),
+        "rooms" => Some(Box::new(RoomsAndCorridors { attempts: 40, max_room_size: 4 })),
+        _ => None,
+    }
+}
+
+// Búsqueda en profundidad con retroceso, el mismo recorrido que hacía maze.py
+pub struct RecursiveBacktracker;
+
+impl MazeGenerator for RecursiveBacktracker {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let mut visited = vec![false; grid.width * grid.height];
+
+        // Pila explícita en lugar de recursión para no desbordar con laberintos grandes
+        let start = (rng.range(grid.width), rng.range(grid.height));
+        visited[grid.index(start)] = true;
+        let mut stack = vec![(start, shuffled_directions(rng), 0)];
+
+        while let Some((cell, dirs, next)) = stack.last_mut() {
+            if *next == dirs.len() {
+                stack.pop();
+                continue;
+            }
+
+            let (x, y) = *cell;
+            let (dx, dy) = dirs[*next];
+            *next += 1;
+
+            let nx = x as isize + dx;
+            let ny = y as isize + dy;
+            if nx < 0 || ny < 0 || nx >= grid.width as isize || ny >= grid.height as isize {
+                continue;
+            }
+            let neighbor = (nx as usize, ny as usize);
+            if visited[grid.index(neighbor)] {
+                continue;
+            }
+
+            grid.carve((x, y), neighbor);
+            visited[grid.index(neighbor)] = true;
+            stack.push((neighbor, shuffled_directions(rng), 0));
+        }
+    }
+}
+
+fn shuffled_directions(rng: &mut Rng) -> [(isize, isize); 4] {
+    let mut dirs = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+    rng.shuffle(&mut dirs);
+    dirs
+}
+
+// Prim aleatorizado: crece desde una celda eligiendo al azar de la frontera
+pub struct Prim;
+
+impl MazeGenerator for Prim {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let mut in_maze = vec![false; grid.width * grid.height];
+        let mut in_frontier = vec![false; grid.width * grid.height];
+        let mut frontier = Vec::new();
+
+        let start = (rng.range(grid.width), rng.range(grid.height));
+        in_maze[grid.index(start)] = true;
+        for n in grid.neighbors(start) {
+            in_frontier[grid.index(n)] = true;
+            frontier.push(n);
+        }
+
+        while !frontier.is_empty() {
+            let cell = frontier.swap_remove(rng.range(frontier.len()));
+
+            let linked: Vec<_> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| in_maze[grid.index(n)])
+                .collect();
+            let target = linked[rng.range(linked.len())];
+            grid.carve(cell, target);
+            in_maze[grid.index(cell)] = true;
+
+            for n in grid.neighbors(cell) {
+                let i = grid.index(n);
+                if !in_maze[i] && !in_frontier[i] {
+                    in_frontier[i] = true;
+                    frontier.push(n);
+                }
+            }
+        }
+    }
+}
+
+// Kruskal aleatorizado: une conjuntos disjuntos recorriendo las paredes al azar
+pub struct Kruskal;
+
+impl MazeGenerator for Kruskal {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let mut edges = Vec::new();
+        for (x, y) in grid.cells() {
+            if x + 1 < grid.width {
+                edges.push(((x, y), (x + 1, y)));
+            }
+            if y + 1 < grid.height {
+                edges.push(((x, y), (x, y + 1)));
+            }
+        }
+        rng.shuffle(&mut edges);
+
+        let mut sets = DisjointSet::new(grid.width * grid.height);
+        for (a, b) in edges {
+            if sets.union(grid.index(a), grid.index(b)) {
+                grid.carve(a, b);
+            }
+        }
+    }
+}
+
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        DisjointSet {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    // Devuelve true si los conjuntos estaban separados
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let ra = self.find(a);
+        let rb = self.find(b);
+        if ra == rb {
+            return false;
+        }
+        self.parent[rb] = ra;
+        true
+    }
+}
+
+// Wilson: caminatas aleatorias con borrado de ciclos, sin sesgo
+pub struct Wilson;
+
+impl MazeGenerator for Wilson {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let total = grid.width * grid.height;
+        let mut in_maze = vec![false; total];
+        let mut remaining = grid.cells();
+        rng.shuffle(&mut remaining);
+
+        let first = remaining.pop().unwrap();
+        in_maze[grid.index(first)] = true;
+
+        // next_step[i] guarda la última dirección tomada desde la celda i
+        let mut next_step: Vec<Option<(usize, usize)>> = vec![None; total];
+
+        while let Some(start) = remaining.pop() {
+            if in_maze[grid.index(start)] {
+                continue;
+            }
+
+            let mut cell = start;
+            while !in_maze[grid.index(cell)] {
+                let options = grid.neighbors(cell);
+                let next = options[rng.range(options.len())];
+                next_step[grid.index(cell)] = Some(next);
+                cell = next;
+            }
+
+            let mut cell = start;
+            while !in_maze[grid.index(cell)] {
+                let next = next_step[grid.index(cell)].unwrap();
+                grid.carve(cell, next);
+                in_maze[grid.index(cell)] = true;
+                cell = next;
+            }
+        }
+    }
+}
+
+// Aldous-Broder: caminata aleatoria pura hasta haber visitado todo
+pub struct AldousBroder;
+
+impl MazeGenerator for AldousBroder {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let mut visited = vec![false; grid.width * grid.height];
+        let mut cell = (rng.range(grid.width), rng.range(grid.height));
+        visited[grid.index(cell)] = true;
+        let mut unvisited = grid.width * grid.height - 1;
+
+        while unvisited > 0 {
+            let options = grid.neighbors(cell);
+            let next = options[rng.range(options.len())];
+            if !visited[grid.index(next)] {
+                grid.carve(cell, next);
+                visited[grid.index(next)] = true;
+                unvisited -= 1;
+            }
+            cell = next;
+        }
+    }
+}
+
+// Eller: fila por fila, manteniendo conjuntos de celdas conectadas
+pub struct Eller;
+
+impl MazeGenerator for Eller {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let width = grid.width;
+        let mut sets: Vec<usize> = (0..width).collect();
+        let mut next_set = width;
+
+        for y in 0..grid.height {
+            let last_row = y + 1 == grid.height;
+
+            // Unir celdas vecinas de la fila
+            for x in 0..width.saturating_sub(1) {
+                if sets[x] != sets[x + 1] && (last_row || rng.range(2) == 0) {
+                    grid.carve((x, y), (x + 1, y));
+                    let old = sets[x + 1];
+                    let new = sets[x];
+                    for set in sets.iter_mut() {
+                        if *set == old {
+                            *set = new;
+                        }
+                    }
+                }
+            }
+
+            if last_row {
+                break;
+            }
+
+            // Al menos una bajada por conjunto
+            let mut next_sets = vec![usize::MAX; width];
+            let mut order: Vec<usize> = (0..width).collect();
+            rng.shuffle(&mut order);
+            let mut has_descent: Vec<usize> = Vec::new();
+            for &x in &order {
+                if !has_descent.contains(&sets[x]) || rng.range(3) == 0 {
+                    has_descent.push(sets[x]);
+                    grid.carve((x, y), (x, y + 1));
+                    next_sets[x] = sets[x];
+                }
+            }
+
+            for set in next_sets.iter_mut() {
+                if *set == usize::MAX {
+                    *set = next_set;
+                    next_set += 1;
+                }
+            }
+            sets = next_sets;
+        }
+    }
+}
+
+// Sidewinder: tramos horizontales con una salida hacia arriba por tramo
+pub struct Sidewinder;
+
+impl MazeGenerator for Sidewinder {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        for y in 0..grid.height {
+            let mut run_start = 0;
+            for x in 0..grid.width {
+                let at_east_edge = x + 1 == grid.width;
+                let close_run = y == 0 || at_east_edge || rng.range(2) == 0;
+
+                if !close_run || (y == 0 && !at_east_edge) {
+                    grid.carve((x, y), (x + 1, y));
+                } else if y > 0 {
+                    let member = run_start + rng.range(x - run_start + 1);
+                    grid.carve((member, y), (member, y - 1));
+                    run_start = x + 1;
+                }
+            }
+        }
+    }
+}
+
+// Árbol binario: cada celda abre hacia el norte o hacia el oeste
+pub struct BinaryTree;
+
+impl MazeGenerator for BinaryTree {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        for (x, y) in grid.cells() {
+            let mut options = Vec::with_capacity(2);
+            if y > 0 {
+                options.push((x, y - 1));
+            }
+            if x > 0 {
+                options.push((x - 1, y));
+            }
+            if !options.is_empty() {
+                let target = options[rng.range(options.len())];
+                grid.carve((x, y), target);
+            }
+        }
+    }
+}
+
+// Growing tree: mezcla entre backtracker (celda más nueva) y Prim (celda al azar)
+pub struct GrowingTree {
+    pub newest_percent: usize,
+}
+
+impl MazeGenerator for GrowingTree {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let mut visited = vec![false; grid.width * grid.height];
+        let start = (rng.range(grid.width), rng.range(grid.height));
+        visited[grid.index(start)] = true;
+        let mut active = vec![start];
+
+        while !active.is_empty() {
+            let index = if rng.range(100) < self.newest_percent {
+                active.len() - 1
+            } else {
+                rng.range(active.len())
+            };
+            let cell = active[index];
+
+            let unvisited: Vec<_> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| !visited[grid.index(n)])
+                .collect();
+
+            if unvisited.is_empty() {
+                active.remove(index);
+                continue;
+            }
+
+            let next = unvisited[rng.range(unvisited.len())];
+            grid.carve(cell, next);
+            visited[grid.index(next)] = true;
+            active.push(next);
+        }
+    }
+}
+
+// Salas rectangulares colocadas al azar y unidas por pasillos en L. Las celdas
+// que no quedan en ninguna sala ni pasillo se convierten en roca.
+pub struct RoomsAndCorridors {
+    pub attempts: usize,
+    pub max_room_size: usize,
+}
+
+struct Room {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Room {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    // Deja al menos una celda de separación entre salas
+    fn overlaps(&self, other: &Room) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+}
+
+impl MazeGenerator for RoomsAndCorridors {
+    fn carve(&self, grid: &mut CellGrid, rng: &mut Rng) {
+        let max_size = self.max_room_size.max(1);
+        let mut rooms: Vec<Room> = Vec::new();
+
+        for _ in 0..self.attempts {
+            let width = (1 + rng.range(max_size)).min(grid.width);
+            let height = (1 + rng.range(max_size)).min(grid.height);
+            let room = Room {
+                x: rng.range(grid.width - width + 1),
+                y: rng.range(grid.height - height + 1),
+                width,
+                height,
+            };
+            if rooms.iter().all(|other| !room.overlaps(other)) {
+                rooms.push(room);
+            }
+        }
+
+        // Siempre al menos dos salas para que inicio y meta no coincidan
+        if rooms.len() < 2 {
+            rooms = vec![
+                Room { x: 0, y: 0, width: 1, height: 1 },
+                Room { x: grid.width - 1, y: grid.height - 1, width: 1, height: 1 },
+            ];
+        }
+
+        for room in &rooms {
+            for y in room.y..room.y + room.height {
+                for x in room.x..room.x + room.width {
+                    if x + 1 < room.x + room.width {
+                        grid.carve((x, y), (x + 1, y));
+                    }
+                    if y + 1 < room.y + room.height {
+                        grid.carve((x, y), (x, y + 1));
+                    }
+                }
+            }
+        }
+
+        for pair in rooms.windows(2) {
+            let from = pair[0].center();
+            let to = pair[1].center();
+            if rng.range(2) == 0 {
+                carve_corridor(grid, from, (to.0, from.1));
+                carve_corridor(grid, (to.0, from.1), to);
+            } else {
+                carve_corridor(grid, from, (from.0, to.1));
+                carve_corridor(grid, (from.0, to.1), to);
+            }
+        }
+
+        let start = rooms[0].center();
+        let goal = rooms
+            .iter()
+            .map(|room| room.center())
+            .max_by_key(|&(x, y)| x.abs_diff(start.0) + y.abs_diff(start.1))
+            .unwrap();
+        grid.start = start;
+        grid.goal = goal;
+    }
+}
+
+// Pasillo recto entre dos celdas en la misma fila o columna
+fn carve_corridor(grid: &mut CellGrid, from: (usize, usize), to: (usize, usize)) {
+    let mut cell = from;
+    while cell != to {
+        let next = if cell.0 < to.0 {
+            (cell.0 + 1, cell.1)
+        } else if cell.0 > to.0 {
+            (cell.0 - 1, cell.1)
+        } else if cell.1 < to.1 {
+            (cell.0, cell.1 + 1)
+        } else {
+            (cell.0, cell.1 - 1)
+        };
+        grid.carve(cell, next);
+        cell = next;
+    }
+}
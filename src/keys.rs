@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use nalgebra_glm::Vec2;
+use crate::cast_ray::Side;
+use crate::color::Color;
+use crate::doors::door_side;
+use crate::maze::{is_key, is_passable, key_for_door};
+use crate::player::touches_item;
+use crate::rng::Rng;
+use crate::solver::{find_cell, solve, Cell, Strategy};
+use crate::texture::Texture;
+
+// Orden en que se reparten las llaves en los laberintos generados
+pub const KEY_ORDER: [char; 3] = ['r', 'b', 'y'];
+
+// Color de una llave o de su puerta
+pub fn key_color(cell: char) -> Color {
+    match cell.to_ascii_lowercase() {
+        'r' => Color::new(220, 40, 40),
+        'b' => Color::new(40, 90, 230),
+        _ => Color::new(240, 210, 40),
+    }
+}
+
+// Llaves que quedan en el piso y las que ya lleva el jugador
+pub struct Keys {
+    items: Vec<(Cell, char)>,
+    held: Vec<char>,
+}
+
+impl Keys {
+    // Saca las llaves de la rejilla y deja piso en su lugar
+    pub fn extract(maze: &mut [Vec<char>]) -> Self {
+        let mut items = Vec::new();
+        for (row, line) in maze.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                if is_key(*cell) {
+                    items.push(((col, row), *cell));
+                    *cell = ' ';
+                }
+            }
+        }
+        Keys { items, held: Vec::new() }
+    }
+
+    pub fn items(&self) -> &[(Cell, char)] {
+        &self.items
+    }
+
+    pub fn held(&self) -> &[char] {
+        &self.held
+    }
+
+    // Las llaves que toca el jugador en `pos` salen del piso y pasan a las que
+    // lleva, que son las que abren las puertas de su color
+    pub fn pick_up(&mut self, pos: Vec2, block_size: usize) {
+        let held = &mut self.held;
+        self.items.retain(|&(cell, key)| {
+            let touched = touches_item(pos, cell, block_size);
+            if touched {
+                held.push(key);
+            }
+            !touched
+        });
+    }
+}
+
+// Casillas alcanzables desde `start` con las llaves de `held`, y las llaves
+// que hay en ellas
+fn reachable(maze: &[Vec<char>], start: Cell, held: &[char]) -> (Vec<Cell>, Vec<char>) {
+    let can_enter = |(col, row): Cell| {
+        maze.get(row)
+            .and_then(|line| line.get(col))
+            .is_some_and(|&cell| is_passable(cell) && key_for_door(cell).is_none_or(|key| held.contains(&key)))
+    };
+
+    let width = maze.iter().map(|line| line.len()).max().unwrap_or(0);
+    let mut visited = vec![false; width * maze.len()];
+    let mut cells = Vec::new();
+    let mut found = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    visited[start.1 * width + start.0] = true;
+
+    while let Some((col, row)) = queue.pop_front() {
+        cells.push((col, row));
+        let cell = maze[row][col];
+        if is_key(cell) && !found.contains(&cell) {
+            found.push(cell);
+        }
+        let neighbors = [
+            col.checked_sub(1).map(|col| (col, row)),
+            Some((col + 1, row)),
+            row.checked_sub(1).map(|row| (col, row)),
+            Some((col, row + 1)),
+        ];
+        for (ncol, nrow) in neighbors.into_iter().flatten() {
+            // `can_enter` va primero: descarta lo que queda fuera de la rejilla
+            if can_enter((ncol, nrow)) && !visited[nrow * width + ncol] {
+                visited[nrow * width + ncol] = true;
+                queue.push_back((ncol, nrow));
+            }
+        }
+    }
+
+    (cells, found)
+}
+
+// Recoge todas las llaves a su alcance y vuelve a explorar con ellas hasta
+// que no aparecen más. La rejilla debe estar cerrada por paredes.
+pub fn is_solvable(maze: &[Vec<char>], start: Cell, goal: Cell) -> bool {
+    let mut held = Vec::new();
+    loop {
+        let (visited, found) = reachable(maze, start, &held);
+        if visited.contains(&goal) {
+            return true;
+        }
+        if found.len() == held.len() {
+            return false;
+        }
+        held = found;
+    }
+}
+
+// Pone hasta `count` puertas con cerradura en los pasos del camino de `p` a
+// `g`, en orden, y la llave de cada una en una casilla a la que se llega con
+// las llaves anteriores, así el laberinto siempre tiene solución.
+// Devuelve cuántas parejas se pusieron.
+pub fn place_keys(maze: &mut [Vec<char>], count: usize, rng: &mut Rng) -> usize {
+    let (Some(start), Some(goal)) = (find_cell(maze, 'p'), find_cell(maze, 'g')) else {
+        return 0;
+    };
+    let Some(path) = solve(maze, start, goal, Strategy::Bfs) else {
+        return 0;
+    };
+
+    // Huecos entre celdas que cruza el camino; primero los que no se pueden rodear
+    let mut gaps: Vec<Vec<Cell>> = Vec::new();
+    for &cell in &path {
+        if let Some(gap) = gap_at(maze, cell) {
+            if !gaps.contains(&gap) {
+                gaps.push(gap);
+            }
+        }
+    }
+    let (mut chosen, others): (Vec<_>, Vec<_>) = gaps
+        .into_iter()
+        .partition(|gap| is_chokepoint(maze, gap, start, goal));
+    if chosen.len() < count {
+        chosen.extend(others);
+        chosen.sort_by_key(|gap| path.iter().position(|cell| gap.contains(cell)));
+    }
+
+    // Repartidas a lo largo del camino
+    let count = count.min(chosen.len()).min(KEY_ORDER.len());
+    let doors: Vec<Vec<Cell>> = (0..count)
+        .map(|i| chosen[(i + 1) * chosen.len() / (count + 1)].clone())
+        .collect();
+
+    for (gap, key) in doors.iter().zip(KEY_ORDER) {
+        for &(col, row) in gap {
+            maze[row][col] = key.to_ascii_uppercase();
+        }
+    }
+
+    let mut placed = 0;
+    for (index, (gap, key)) in doors.iter().zip(KEY_ORDER).enumerate() {
+        let (visited, _) = reachable(maze, start, &KEY_ORDER[..index]);
+        let mut floor: Vec<Cell> = visited
+            .into_iter()
+            .filter(|&(col, row)| maze[row][col] == ' ' && row % 2 == 1 && col % 3 != 0)
+            .collect();
+        floor.sort();
+
+        // Sin sitio para la llave, la puerta se quita
+        if floor.is_empty() {
+            for &(col, row) in gap {
+                maze[row][col] = ' ';
+            }
+            continue;
+        }
+        let (col, row) = floor[rng.range(floor.len())];
+        maze[row][col] = key;
+        placed += 1;
+    }
+
+    placed
+}
+
+// Caracteres del hueco entre celdas que contiene `cell`, si es uno: dos en
+// las filas de paredes horizontales y uno en las columnas de `|`. Sólo vale si
+// la puerta que se ponga ahí tiene una orientación clara.
+fn gap_at(maze: &[Vec<char>], (col, row): Cell) -> Option<Vec<Cell>> {
+    let gap = if row % 2 == 0 && col % 3 != 0 {
+        let first = col - (col - 1) % 3;
+        vec![(first, row), (first + 1, row)]
+    } else if row % 2 == 1 && col % 3 == 0 {
+        vec![(col, row)]
+    } else {
+        return None;
+    };
+
+    if gap.iter().any(|&(col, row)| maze[row][col] != ' ') {
+        return None;
+    }
+
+    let expected = if row % 2 == 0 { Side::Horizontal } else { Side::Vertical };
+    let mut trial: Vec<Vec<char>> = maze.to_vec();
+    for &(col, row) in &gap {
+        trial[row][col] = 'D';
+    }
+    gap.iter()
+        .all(|&cell| door_side(&trial, cell) == Some(expected))
+        .then_some(gap)
+}
+
+// Si al tapar el hueco deja de haber camino, la puerta no se puede rodear
+fn is_chokepoint(maze: &[Vec<char>], gap: &[Cell], start: Cell, goal: Cell) -> bool {
+    let mut blocked: Vec<Vec<char>> = maze.to_vec();
+    for &(col, row) in gap {
+        blocked[row][col] = '+';
+    }
+    solve(&blocked, start, goal, Strategy::Bfs).is_none()
+}
+
+// Llave vista de frente: anillo arriba y paletón abajo, del color de su puerta
+pub fn key_texture(color: Color) -> Texture {
+    let size = 32;
+    Texture::from_fn(size, size, |x, y| {
+        let (dx, dy) = (x as f32 - 15.5, y as f32 - 8.5);
+        let ring = (dx * dx + dy * dy).sqrt();
+        let in_ring = (3.5..7.0).contains(&ring);
+        let shaft = (14..18).contains(&x) && (15..31).contains(&y);
+        let teeth = (18..23).contains(&x) && ((22..25).contains(&y) || (27..30).contains(&y));
+        (in_ring || shaft || teeth).then_some(color)
+    })
+}
@@ -0,0 +1,42 @@
+use crate::color::Color;
+
+// Las paredes con y constante se ven más oscuras, como si la luz viniera de un lado
+pub const HORIZONTAL_SIDE_BRIGHTNESS: f32 = 0.7;
+
+// Mezcla dos colores: con `t` en 0.0 queda `a` y en 1.0 queda `b`
+pub fn mix(a: u32, b: u32, t: f32) -> u32 {
+    let channel = |shift: u32| {
+        let from = ((a >> shift) & 0xFF) as f32;
+        let to = ((b >> shift) & 0xFF) as f32;
+        ((from + (to - from) * t) as u32) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
+
+// Niebla exponencial: a `distance` casillas queda visible exp(-density * distance)
+// del color original y el resto es color de niebla.
+pub struct Fog {
+    pub color: Color,
+    pub density: f32,
+}
+
+impl Fog {
+    pub fn new(color: Color, density: f32) -> Self {
+        Fog { color, density }
+    }
+
+    pub fn visibility(&self, distance: f32) -> f32 {
+        (-self.density * distance.max(0.0)).exp()
+    }
+
+    // Oscurece el texel por `brightness` y lo mezcla con la niebla según `visibility`
+    pub fn apply(&self, texel: u32, visibility: f32, brightness: f32) -> u32 {
+        let amount = visibility * brightness;
+        let channel = |shift: u32, fog: u8| {
+            let value = ((texel >> shift) & 0xFF) as f32;
+            let fog_part = fog as f32 * (1.0 - visibility);
+            ((value * amount + fog_part).min(255.0) as u32) << shift
+        };
+        channel(16, self.color.r) | channel(8, self.color.g) | channel(0, self.color.b)
+    }
+}
@@ -5,37 +5,50 @@ mod cast_ray;
 mod color;
 mod events;
 mod audio_player;
-
-use minifb::{Window, WindowOptions, Key, MouseMode};
+mod rng;
+mod generator;
+mod config;
+mod braid;
+mod solver;
+mod font;
+mod minimap;
+mod menu;
+mod texture;
+mod lighting;
+mod sprite;
+mod doors;
+mod keys;
+mod collectibles;
+mod enemies;
+mod world;
+
+use minifb::{Window, WindowOptions, Key};
 use nalgebra_glm::Vec2;
-use std::f32::consts::PI;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use std::process::Command;
 use crate::framebuffer::Framebuffer;
-use crate::maze::load_maze;
+use crate::generator::{generator_by_name, GENERATOR_NAMES};
+use crate::config::Config;
+use crate::maze::{generate, is_door, load_level, validate_maze, Ceiling, Level, DOOR};
 use crate::player::Player;
-use crate::cast_ray::cast_ray;
-use crate::events::process_events;
+use crate::cast_ray::{cast_ray, Side};
+use crate::events::{process_events, InputState};
 use crate::color::Color;
-use image::GenericImageView;
 use crate::audio_player::AudioPlayer;
-
-
-fn load_texture(file_path: &str) -> Vec<u32> {
-  let img = image::open(file_path).expect("Failed to load texture");
-  let (width, height) = img.dimensions();
-  let mut texture = Vec::new();
-
-  for y in 0..height {
-      for x in 0..width {
-          let pixel = img.get_pixel(x, y);
-          let color = ((pixel[0] as u32) << 16) | ((pixel[1] as u32) << 8) | (pixel[2] as u32);
-          texture.push(color);
-      }
-  }
-
-  texture
-}
+use crate::rng::{random_seed, Rng};
+use crate::braid::{add_loops, remove_dead_ends};
+use crate::solver::{find_cell, solve, Cell};
+use crate::font::draw_glyph;
+use crate::minimap::{draw_maze_thumbnail, thumbnail_scale};
+use crate::menu::{load_levels, select_level, LEVELS_DIR};
+use crate::texture::Texture;
+use crate::lighting::{mix, Fog, HORIZONTAL_SIDE_BRIGHTNESS};
+use crate::sprite::{goal_texture, render_sprites, Sprite};
+use crate::doors::{door_texture, locked_door_texture, Doors};
+use crate::keys::{key_color, key_texture, place_keys, Keys, KEY_ORDER};
+use crate::collectibles::{coin_color, coin_texture, scatter_coins, Collectibles};
+use crate::enemies::{enemy_color, enemy_texture, spawn_enemies, Enemies};
+use crate::world::World;
 
 
 fn cell_to_color(cell: char) -> Color {
@@ -53,22 +66,19 @@ fn draw_cell_with_texture(
   xo: usize,
   yo: usize,
   block_size: usize,
-  texture: &[u32],
-  texture_width: usize,
-  texture_height: usize,
+  texture: &Texture,
 ) {
   for x in 0..block_size {
       for y in 0..block_size {
-          let tx = (x * texture_width / block_size) % texture_width;
-          let ty = (y * texture_height / block_size) % texture_height;
-          let color = texture[ty * texture_width + tx];
-          framebuffer.point(xo + x, yo + y, color);
+          let tx = x * texture.width / block_size;
+          let ty = y * texture.height / block_size;
+          framebuffer.point(xo + x, yo + y, texture.get(tx, ty));
       }
   }
 }
 
 
-fn find_player_start(maze: &Vec<Vec<char>>) -> Option<Vec2> {
+fn find_player_start(maze: &[Vec<char>]) -> Option<Vec2> {
     for (y, row) in maze.iter().enumerate() {
         for (x, &cell) in row.iter().enumerate() {
             if cell == 'p' {
@@ -79,48 +89,61 @@ fn find_player_start(maze: &Vec<Vec<char>>) -> Option<Vec2> {
     None
 }
 
+// Texturas del nivel: paredes y puertas por carácter, piso y techo
+struct Textures {
+  cells: HashMap<char, Texture>,
+  floor: Texture,
+  ceiling: Texture,
+}
+
+impl Textures {
+  // Las texturas de la cabecera ya se revisaron al cargar el nivel; aquí sólo
+  // puede fallar una de las que vienen por defecto
+  fn load(level: &Level) -> Result<Self, String> {
+      let load = |path: &str| Texture::load(path).map_err(|err| format!("{}: {}", path, err));
+
+      let mut cells = HashMap::new();
+      for wall in ['+', '-', '|'] {
+          cells.insert(wall, load(level.texture_for(wall))?);
+      }
+      // Las puertas usan la textura de la cabecera si el nivel trae una
+      cells.insert(DOOR, door_texture());
+      for key in KEY_ORDER {
+          cells.insert(key.to_ascii_uppercase(), locked_door_texture(key_color(key)));
+      }
+      for door in std::iter::once(DOOR).chain(KEY_ORDER.map(|key| key.to_ascii_uppercase())) {
+          if let Some(path) = level.textures.get(&door) {
+              cells.insert(door, load(path)?);
+          }
+      }
+
+      let ceiling = match &level.ceiling {
+          Ceiling::Color(color) => Texture::solid(*color),
+          Ceiling::Texture(path) => load(path)?,
+      };
+      Ok(Textures { cells, floor: load(&level.floor)?, ceiling })
+  }
+}
+
 fn render2d(
   framebuffer: &mut Framebuffer,
   player: &Player,
-  maze: &Vec<Vec<char>>,
+  world: &World,
+  textures: &Textures,
   block_size: usize,
-  wall_texture_1: &[u32],
-  wall_texture_2: &[u32],
-  wall_texture_3: &[u32],
-  texture_width: usize,
-  texture_height: usize,
+  hint_path: &[Cell],
 ) {
-  for row in 0..maze.len() {
-      for col in 0..maze[row].len() {
-          let cell = maze[row][col];
+  for (row, line) in world.maze.iter().enumerate() {
+      for (col, &cell) in line.iter().enumerate() {
           match cell {
-              '+' => draw_cell_with_texture(
-                  framebuffer,
-                  col * block_size,
-                  row * block_size,
-                  block_size,
-                  wall_texture_1,
-                  texture_width,
-                  texture_height,
-              ),
-              '-' => draw_cell_with_texture(
-                  framebuffer,
-                  col * block_size,
-                  row * block_size,
-                  block_size,
-                  wall_texture_2,
-                  texture_width,
-                  texture_height,
-              ),
-              '|' => draw_cell_with_texture(
+              '+' | '-' | '|' => draw_cell_with_texture(
                   framebuffer,
                   col * block_size,
                   row * block_size,
                   block_size,
-                  wall_texture_3,
-                  texture_width,
-                  texture_height,
+                  &textures.cells[&cell],
               ),
+              c if is_door(c) => draw_door_2d(framebuffer, &world.doors, (col, row), block_size, &textures.cells[&c]),
               'p' | 'g' => {
                   let color = cell_to_color(cell);
                   framebuffer.set_current_color(color);
@@ -135,6 +158,10 @@ fn render2d(
       }
   }
 
+  draw_hint_path(framebuffer, hint_path, 0, 0, block_size);
+  draw_keys(framebuffer, &world.keys, 0, 0, block_size);
+  draw_coins(framebuffer, &world.coins, 0, 0, block_size);
+  draw_enemies(framebuffer, &world.enemies, 0, 0, block_size, block_size);
 
   framebuffer.set_current_color(Color::new(255, 255, 0));
   framebuffer.point(player.pos.x as usize, player.pos.y as usize, Color::new(255, 255, 0).to_hex());
@@ -144,7 +171,130 @@ fn render2d(
   for i in 0..num_rays {
       let current_ray = i as f32 / num_rays as f32;
       let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-      cast_ray(framebuffer, &maze, player, a, block_size, true);
+      let intersect = cast_ray(&world.maze, &world.doors, player, a, block_size);
+      draw_ray(framebuffer, player.pos, intersect.hit);
+  }
+}
+
+// Sólo se dibuja la parte del panel que sigue cerrada, igual que en 3D
+fn draw_door_2d(
+  framebuffer: &mut Framebuffer,
+  doors: &Doors,
+  (col, row): Cell,
+  block_size: usize,
+  texture: &Texture,
+) {
+  let Some(door) = doors.get((col, row)) else {
+      return;
+  };
+  let hidden = (door.open * block_size as f32) as usize;
+
+  for x in 0..block_size {
+      for y in 0..block_size {
+          let along = match door.side {
+              Side::Vertical => y,
+              Side::Horizontal => x,
+          };
+          if along < hidden {
+              continue;
+          }
+          let color = texture.get(x * texture.width / block_size, y * texture.height / block_size);
+          framebuffer.point(col * block_size + x, row * block_size + y, color);
+      }
+  }
+}
+
+fn draw_ray(framebuffer: &mut Framebuffer, from: Vec2, to: Vec2) {
+  let color = Color::new(255, 221, 221).to_hex(); // Color de la línea de rayos
+  let steps = (to - from).norm().ceil() as usize;
+
+  for step in 0..steps {
+      let point = from + (to - from) * (step as f32 / steps as f32);
+      framebuffer.point(point.x as usize, point.y as usize, color);
+  }
+}
+
+
+// Marca el centro de cada casilla del camino sugerido
+fn draw_hint_path(
+  framebuffer: &mut Framebuffer,
+  path: &[Cell],
+  origin_x: usize,
+  origin_y: usize,
+  cell_size: usize,
+) {
+  let color = Color::new(0, 200, 255);
+  for &cell in path {
+      draw_cell_dot(framebuffer, cell, origin_x, origin_y, cell_size, color);
+  }
+}
+
+// Las llaves que siguen en el piso, con el color de su puerta
+fn draw_keys(
+  framebuffer: &mut Framebuffer,
+  keys: &Keys,
+  origin_x: usize,
+  origin_y: usize,
+  cell_size: usize,
+) {
+  for &(cell, key) in keys.items() {
+      draw_cell_dot(framebuffer, cell, origin_x, origin_y, cell_size, key_color(key));
+  }
+}
+
+fn draw_coins(
+  framebuffer: &mut Framebuffer,
+  coins: &Collectibles,
+  origin_x: usize,
+  origin_y: usize,
+  cell_size: usize,
+) {
+  for &cell in coins.items() {
+      draw_cell_dot(framebuffer, cell, origin_x, origin_y, cell_size, coin_color());
+  }
+}
+
+// Los enemigos no están siempre en el centro de una casilla, así que se
+// dibujan en su posición escalada
+fn draw_enemies(
+  framebuffer: &mut Framebuffer,
+  enemies: &Enemies,
+  origin_x: usize,
+  origin_y: usize,
+  cell_size: usize,
+  block_size: usize,
+) {
+  let dot_size = (cell_size / 2).max(2);
+  for enemy in enemies.iter() {
+      // Más oscuros mientras patrullan
+      let color = if enemy.chasing { enemy_color() } else { Color::new(140, 30, 50) };
+      let center = enemy.pos / block_size as f32 * cell_size as f32;
+      let x0 = ((origin_x as f32 + center.x) as usize).saturating_sub(dot_size / 2);
+      let y0 = ((origin_y as f32 + center.y) as usize).saturating_sub(dot_size / 2);
+      for x in 0..dot_size {
+          for y in 0..dot_size {
+              framebuffer.point(x0 + x, y0 + y, color.to_hex());
+          }
+      }
+  }
+}
+
+fn draw_cell_dot(
+  framebuffer: &mut Framebuffer,
+  (col, row): Cell,
+  origin_x: usize,
+  origin_y: usize,
+  cell_size: usize,
+  color: Color,
+) {
+  let dot_size = (cell_size / 3).max(1);
+  let offset = (cell_size - dot_size) / 2;
+  let x0 = origin_x + col * cell_size + offset;
+  let y0 = origin_y + row * cell_size + offset;
+  for x in 0..dot_size {
+      for y in 0..dot_size {
+          framebuffer.point(x0 + x, y0 + y, color.to_hex());
+      }
   }
 }
 
@@ -152,114 +302,243 @@ fn render2d(
 fn render3d(
   framebuffer: &mut Framebuffer,
   player: &Player,
-  maze: &Vec<Vec<char>>,
+  world: &World,
+  textures: &Textures,
   block_size: usize,
-  wall_texture_1: &[u32],
-  wall_texture_2: &[u32],
-  wall_texture_3: &[u32],
-  texture_width: usize,
-  texture_height: usize,
+  fog: &Fog,
+  zbuffer: &mut [f32],
 ) {
   let num_rays = framebuffer.width;
-  let hh = framebuffer.height as f32 / 2.0;
-  let distance_to_projection_plane = 60.0;
+  let distance_to_projection_plane = player.projection_plane_distance(framebuffer.width);
+  let horizon = player.horizon(framebuffer.height, distance_to_projection_plane);
+  let eye_height = player.eye_height();
 
-  for i in 0..num_rays {
-      let current_ray = i as f32 / num_rays as f32;
-      let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
+  // Primero piso y techo; las paredes se dibujan encima
+  render_floor_and_ceiling(framebuffer, player, &world.maze, textures, block_size, distance_to_projection_plane, fog);
 
-      let intersect = cast_ray(framebuffer, &maze, player, a, block_size, false);
-      let distance_to_wall = intersect.distance;
+  for (i, depth) in zbuffer.iter_mut().enumerate().take(num_rays) {
+      // Cada columna apunta a su posición sobre el plano, no a un ángulo repartido uniforme
+      let column_offset = i as f32 + 0.5 - num_rays as f32 / 2.0;
+      let a = player.a + (column_offset / distance_to_projection_plane).atan();
+
+      let intersect = cast_ray(&world.maze, &world.doors, player, a, block_size);
+
+      // Distancia perpendicular al plano de la cámara, para evitar el efecto ojo de pez
+      let distance_to_wall = intersect.distance * (a - player.a).cos();
+
+      // Los sprites sólo se dibujan en las columnas donde quedan delante de la pared
+      *depth = distance_to_wall;
 
       if distance_to_wall < 0.001 {
           continue;
       }
 
-      match intersect.impact {
-          '+' => apply_texture(framebuffer, i, hh, distance_to_wall, distance_to_projection_plane, wall_texture_1, texture_width, texture_height),
-          '-' => apply_texture(framebuffer, i, hh, distance_to_wall, distance_to_projection_plane, wall_texture_2, texture_width, texture_height),
-          '|' => apply_texture(framebuffer, i, hh, distance_to_wall, distance_to_projection_plane, wall_texture_3, texture_width, texture_height),
-          'p' => {
-              framebuffer.set_current_color(Color::new(0, 255, 0));
-              draw_stake(framebuffer, i, hh, distance_to_wall, distance_to_projection_plane);
-          }
-          'g' => {
-              framebuffer.set_current_color(Color::new(255, 0, 0));
-              draw_stake(framebuffer, i, hh, distance_to_wall, distance_to_projection_plane);
-          }
-          _ => continue, 
+      let stake_height = block_size as f32 * distance_to_projection_plane / distance_to_wall;
+      // La parte de la pared por encima de los ojos queda sobre el horizonte
+      let wall_top = horizon - (1.0 - eye_height) * stake_height;
+
+      // La niebla se mide en casillas para que no dependa de block_size
+      let visibility = fog.visibility(distance_to_wall / block_size as f32);
+      let brightness = match intersect.side {
+          Side::Vertical => 1.0,
+          Side::Horizontal => HORIZONTAL_SIDE_BRIGHTNESS,
+      };
+
+      // Paredes y puertas; cualquier otro carácter no se dibuja
+      if let Some(texture) = textures.cells.get(&intersect.impact) {
+          let shade = |texel| fog.apply(texel, visibility, brightness);
+          apply_texture(framebuffer, i, wall_top, stake_height, intersect.tex_offset, texture, shade);
       }
   }
 }
 
+// `shade` aplica la niebla y la luz de la cara a cada texel
 fn apply_texture(
     framebuffer: &mut Framebuffer,
     i: usize,
-    hh: f32,
-    distance_to_wall: f32,
-    distance_to_projection_plane: f32,
-    texture: &[u32],
-    texture_width: usize,
-    texture_height: usize,
+    wall_top: f32,
+    stake_height: f32,
+    tex_offset: f32,
+    texture: &Texture,
+    shade: impl Fn(u32) -> u32,
 ) {
 
     if i >= framebuffer.width {
         return;
     }
 
-    let stake_height = (hh / distance_to_wall) * distance_to_projection_plane;
-    let stake_top = (hh - (stake_height / 2.0)).max(0.0) as usize;
-    let stake_bottom = (hh + (stake_height / 2.0)).min(framebuffer.height as f32) as usize;
+    // `wall_top` llega sin recortar, para que la textura no se estire al acercarse
+    let stake_top = wall_top.max(0.0) as usize;
+    let stake_bottom = (wall_top + stake_height).min(framebuffer.height as f32).max(0.0) as usize;
+
+    // La columna de la textura sale del punto de impacto, no de la columna de pantalla
+    let tex_x = (tex_offset * texture.width as f32) as usize;
 
     for y in stake_top..stake_bottom {
         if y >= framebuffer.height {
             continue;
         }
 
-        let tex_x = (i as f32 / framebuffer.width as f32 * texture_width as f32) as usize;
-        let tex_y = ((y - stake_top) as f32 / (stake_bottom - stake_top) as f32 * texture_height as f32) as usize;
-
+        let tex_y = ((y as f32 - wall_top) / stake_height * texture.height as f32) as usize;
+        framebuffer.point(i, y, shade(texture.get(tex_x, tex_y)));
+    }
+}
 
-        if tex_x < texture_width && tex_y < texture_height {
-            let color_index = tex_y * texture_width + tex_x;
-            if color_index < texture.len() {
-                let color = texture[color_index];
-                framebuffer.point(i, y, color);
+// Proyecta cada fila de pantalla sobre el plano del piso (o del techo) según
+// la altura de los ojos. La distancia de la fila es la misma en todas las
+// columnas, así que el punto del mundo avanza en línea recta.
+fn render_floor_and_ceiling(
+    framebuffer: &mut Framebuffer,
+    player: &Player,
+    maze: &[Vec<char>],
+    textures: &Textures,
+    block_size: usize,
+    distance_to_projection_plane: f32,
+    fog: &Fog,
+) {
+    let block = block_size as f32;
+    let horizon = player.horizon(framebuffer.height, distance_to_projection_plane);
+    // Distancia vertical de los ojos al piso y al techo
+    let to_floor = player.eye_height() * block;
+    let to_ceiling = block - to_floor;
+
+    let forward = Vec2::new(player.a.cos(), player.a.sin());
+    let right = Vec2::new(-player.a.sin(), player.a.cos());
+
+    for y in 0..framebuffer.height {
+        let rows_from_horizon = (y as f32 + 0.5 - horizon).abs();
+        let is_floor = y as f32 + 0.5 >= horizon;
+        let height = if is_floor { to_floor } else { to_ceiling };
+        let row_distance = height * distance_to_projection_plane / rows_from_horizon;
+        let texture = if is_floor { &textures.floor } else { &textures.ceiling };
+        let visibility = fog.visibility(row_distance / block);
+
+        // Punto del mundo bajo la columna 0 y cuánto avanza por columna
+        let left_offset = 0.5 - framebuffer.width as f32 / 2.0;
+        let step = right * (row_distance / distance_to_projection_plane);
+        let mut world = player.pos + forward * row_distance + step * left_offset;
+
+        for x in 0..framebuffer.width {
+            let u = (world.x / block).rem_euclid(1.0);
+            let v = (world.y / block).rem_euclid(1.0);
+            let mut texel = texture.sample(u, v);
+
+            // La casilla de la meta brilla en el piso
+            if is_floor && cell_at(maze, world, block) == Some('g') {
+                texel = mix(texel, GOAL_GLOW, 0.6);
             }
+
+            framebuffer.point(x, y, fog.apply(texel, visibility, 1.0));
+            world += step;
         }
     }
 }
 
 
-fn draw_stake(
-  framebuffer: &mut Framebuffer,
-  i: usize,
-  hh: f32,
-  distance_to_wall: f32,
-  distance_to_projection_plane: f32,
-) {
-  let stake_height = (hh / distance_to_wall) * distance_to_projection_plane;
-  let stake_top = (hh - (stake_height / 2.0)).max(0.0) as usize;
-  let stake_bottom = (hh + (stake_height / 2.0)).min(framebuffer.height as f32) as usize;
+// Color con el que se ilumina el piso de la meta
+const GOAL_GLOW: u32 = 0xFFC040;
+
+// Casilla del laberinto bajo un punto del mundo, si cae dentro de la rejilla
+fn cell_at(maze: &[Vec<char>], point: Vec2, block: f32) -> Option<char> {
+    if point.x < 0.0 || point.y < 0.0 {
+        return None;
+    }
+    let col = (point.x / block) as usize;
+    let row = (point.y / block) as usize;
+    maze.get(row).and_then(|line| line.get(col)).copied()
+}
 
-  for y in stake_top..stake_bottom {
-      framebuffer.point(i, y, framebuffer.current_color.to_hex());
+fn render_fps(framebuffer: &mut Framebuffer, fps: f32, seed: u64) {
+  let label = "FPS: ";
+  let digits = format!("{:.2}", fps);
+  let text = format!("{}{}  SEED: {}", label, digits, seed);
+
+  let start_x = framebuffer.width - hud_box_width(&text) - 10; 
+  draw_hud_box(framebuffer, &text, start_x, 10);
+}
+
+// Tiempo de la partida, con las penalizaciones por pistas ya sumadas
+fn render_timer(framebuffer: &mut Framebuffer, seconds: f32, par_time: Option<f32>) {
+  let text = match par_time {
+      Some(par) => format!("TIME: {:.1}  PAR: {:.0}", seconds, par),
+      None => format!("TIME: {:.1}", seconds),
+  };
+  draw_hud_box(framebuffer, &text, 10, 10);
+}
+
+// Llaves recogidas: un cuadro del color de cada una detrás del texto
+fn render_keys(framebuffer: &mut Framebuffer, held: &[char]) {
+  let text = format!("KEYS:{}", "  ".repeat(held.len()));
+  let start_x = 10;
+  let start_y = 60;
+  draw_hud_box(framebuffer, &text, start_x, start_y);
+
+  for (i, &key) in held.iter().enumerate() {
+      let x0 = start_x + 10 + (5 + i * 2) * 15 + 10;
+      let color = key_color(key).to_hex();
+      for x in 0..15 {
+          for y in 0..15 {
+              framebuffer.point(x0 + x, start_y + 12 + y, color);
+          }
+      }
   }
 }
 
-fn render_fps(framebuffer: &mut Framebuffer, fps: f32) {
+// Puntos y monedas recogidas, debajo de los FPS
+fn render_score(framebuffer: &mut Framebuffer, coins: &Collectibles) {
+  let text = format!("SCORE: {}  COINS: {}/{}", coins.score(), coins.collected(), coins.total());
+  let start_x = framebuffer.width - hud_box_width(&text) - 10;
+  draw_hud_box(framebuffer, &text, start_x, 60);
+}
+
+// Barra de resistencia abajo a la derecha; naranja mientras está agotada
+fn render_stamina(framebuffer: &mut Framebuffer, player: &Player) {
+  let text = format!("RUN:{}", " ".repeat(8));
+  let start_x = framebuffer.width - hud_box_width(&text) - 10;
+  let start_y = framebuffer.height - 50;
+  draw_hud_box(framebuffer, &text, start_x, start_y);
+
+  let bar_x = start_x + 10 + 5 * 15;
+  let bar_width = 7 * 15;
+  let filled = (player.stamina * bar_width as f32) as usize;
+  let color = if player.exhausted { Color::new(255, 140, 0) } else { Color::new(60, 220, 90) };
+  for x in 0..bar_width {
+      let color = if x < filled { color.to_hex() } else { 0x000040 };
+      for y in 0..15 {
+          framebuffer.point(bar_x + x, start_y + 12 + y, color);
+      }
+  }
+}
+
+// Cartel al llegar a la meta con el tiempo y cuántas monedas se recogieron
+fn render_victory(framebuffer: &mut Framebuffer, seconds: f32, coins: &Collectibles) {
+  let lines = [
+      String::from("YOU WIN!"),
+      format!("TIME: {:.1}", seconds),
+      format!("COINS: {}/{} ({:.0}%)", coins.collected(), coins.total(), coins.completion()),
+  ];
+
+  let width = lines.iter().map(|line| hud_box_width(line)).max().unwrap_or(0);
+  let height = lines.len() * 40 + 20;
+  let start_x = framebuffer.width.saturating_sub(width) / 2;
+  let start_y = framebuffer.height.saturating_sub(height) / 2;
+
+  for (i, line) in lines.iter().enumerate() {
+      let x = start_x + (width - hud_box_width(line)) / 2;
+      draw_hud_box(framebuffer, line, x, start_y + 10 + i * 40);
+  }
+}
+
+fn hud_box_width(text: &str) -> usize {
+  text.len() * 15 + 20
+}
+
+fn draw_hud_box(framebuffer: &mut Framebuffer, text: &str, start_x: usize, start_y: usize) {
   let background_color = Color::new(0, 0, 255); 
   let text_color = Color::new(255, 255, 0); 
 
-  let label = "FPS: ";
-  let digits = format!("{:.2}", fps);
-  let text = format!("{}{}", label, digits);
-  
-  let width = text.len() * 15 + 20; 
+  let width = hud_box_width(text); 
   let height = 40; 
-  let start_x = framebuffer.width - width - 10; 
-  let start_y = 10; 
-
 
   for y in 0..height {
       for x in 0..width {
@@ -270,77 +549,28 @@ fn render_fps(framebuffer: &mut Framebuffer, fps: f32) {
 
   framebuffer.set_current_color(text_color);
   for (i, ch) in text.chars().enumerate() {
-      draw_digit(framebuffer, start_x + 10 + i * 15, start_y + 15, ch); 
-  }
-}
-
-fn draw_digit(framebuffer: &mut Framebuffer, x: usize, y: usize, digit: char) {
-  let digit_map = match digit {
-      '0' => [" ### ", "#   #", "#   #", "#   #", " ### "],
-      '1' => ["  #  ", " ##  ", "  #  ", "  #  ", " ### "],
-      '2' => [" ### ", "#   #", "  ## ", " #   ", "#####"],
-      '3' => [" ### ", "#   #", "  ## ", "#   #", " ### "],
-      '4' => ["#   #", "#   #", "#####", "    #", "    #"],
-      '5' => ["#####", "#    ", "#### ", "    #", "#### "],
-      '6' => [" ### ", "#    ", "#### ", "#   #", " ### "],
-      '7' => ["#####", "    #", "   # ", "  #  ", " #   "],
-      '8' => [" ### ", "#   #", " ### ", "#   #", " ### "],
-      '9' => [" ### ", "#   #", " ####", "    #", " ### "],
-      '.' => ["     ", "     ", "     ", "  ## ", "  ## "],
-      'F' => ["#####", "#    ", "#####", "#    ", "#    "],
-      'P' => ["#### ", "#   #", "#### ", "#    ", "#    "],
-      'S' => [" ####", "#    ", " ### ", "    #", "#### "],
-      _ => ["     ", "     ", "     ", "     ", "     "],
-  };
-
-  let scale = 2; 
-  for (row, line) in digit_map.iter().enumerate() {
-      for (col, ch) in line.chars().enumerate() {
-          if ch == '#' {
-              for dy in 0..scale {
-                  for dx in 0..scale {
-                      framebuffer.point(x + col * scale + dx, y + row * scale + dy, framebuffer.current_color.to_hex());
-                  }
-              }
-          }
-      }
+      draw_glyph(framebuffer, start_x + 10 + i * 15, start_y + 15, ch, 2); 
   }
 }
 
 fn render_minimap(
     framebuffer: &mut Framebuffer,
     player: &Player,
-    maze: &Vec<Vec<char>>,
-    framebuffer_width: usize,
-    framebuffer_height: usize,
+    world: &World,
     minimap_size: usize,
     block_size: usize,
+    hint_path: &[Cell],
 ) {
-    let minimap_scale = minimap_size / maze.len().max(maze[0].len()); 
+    let minimap_scale = thumbnail_scale(&world.maze, minimap_size); 
     let minimap_x = 10; 
-    let minimap_y = framebuffer_height - minimap_size - 10; 
-
-    for (row_index, row) in maze.iter().enumerate() {
-        for (col_index, &cell) in row.iter().enumerate() {
-            let color = match cell {
-                '+' | '-' | '|' => Color::new(100, 100, 100), 
-                'p' => Color::new(0, 255, 0),                 
-                'g' => Color::new(255, 0, 0),                 
-                _ => Color::new(200, 200, 200),               
-            };
-            
-            framebuffer.set_current_color(color);
-
-            let x0 = minimap_x + col_index * minimap_scale;
-            let y0 = minimap_y + row_index * minimap_scale;
-            for x in 0..minimap_scale {
-                for y in 0..minimap_scale {
-                    framebuffer.point(x0 + x, y0 + y, framebuffer.current_color.to_hex());
-                }
-            }
-        }
-    }
+    let minimap_y = framebuffer.height - minimap_size - 10; 
+
+    draw_maze_thumbnail(framebuffer, &world.maze, minimap_x, minimap_y, minimap_scale);
 
+    draw_hint_path(framebuffer, hint_path, minimap_x, minimap_y, minimap_scale);
+    draw_keys(framebuffer, &world.keys, minimap_x, minimap_y, minimap_scale);
+    draw_coins(framebuffer, &world.coins, minimap_x, minimap_y, minimap_scale);
+    draw_enemies(framebuffer, &world.enemies, minimap_x, minimap_y, minimap_scale, block_size);
 
     let player_minimap_x = minimap_x + (player.pos.x / block_size as f32 * minimap_scale as f32) as usize;
     let player_minimap_y = minimap_y + (player.pos.y / block_size as f32 * minimap_scale as f32) as usize;
@@ -349,80 +579,61 @@ fn render_minimap(
     framebuffer.point(player_minimap_x, player_minimap_y, framebuffer.current_color.to_hex());
 }
 
-fn draw_char(framebuffer: &mut Framebuffer, x: usize, y: usize, ch: char, scale: usize) {
-    let char_map = match ch {
-        'P' => ["#### ", "#   #", "#### ", "#    ", "#    "],
-        'r' => ["#### ", "#   #", "#### ", "# #  ", "#  # "],
-        'e' => [" ### ", "#   #", "#####", "#    ", " ### "],
-        's' => [" ####", "#    ", " ### ", "    #", "#### "],
-        'E' => ["#####", "#    ", "#### ", "#    ", "#####"],
-        'n' => ["     ", "     ", "###  ", "#  # ", "#  # "],
-        't' => ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
-        'o' => ["     ", "     ", " ### ", "#   #", " ### "],
-        'S' => ["#### ", "#    ", " ### ", "    #", "#### "],
-        ' ' => ["     ", "     ", "     ", "     ", "     "],
-        'a' => ["     ", " ### ", "#   #", "#####", "#   #"],
-        't' => ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
-        _ => ["     ", "     ", "     ", "     ", "     "],  
+// Nivel "Random": laberinto generado con la semilla y opciones de la línea de comandos
+fn generate_level(config: &Config, seed: u64) -> Level {
+    let mut maze = match &config.algorithm {
+        Some(name) => match generator_by_name(name) {
+            Some(generator) => generator.generate(10, 10, seed),
+            None => {
+                eprintln!(
+                    "Unknown maze algorithm '{}', expected one of: {}",
+                    name,
+                    GENERATOR_NAMES.join(", ")
+                );
+                std::process::exit(1);
+            }
+        },
+        None => generate(10, 10, seed),
     };
 
-    for (row, line) in char_map.iter().enumerate() {
-        for (col, pixel) in line.chars().enumerate() {
-            if pixel == '#' {
-                for dy in 0..scale {
-                    for dx in 0..scale {
-                        framebuffer.point(x + col * scale + dx, y + row * scale + dy, framebuffer.current_color.to_hex());
-                    }
-                }
-            }
-        }
-    }
-}
+    let mut braid_rng = Rng::new(seed.wrapping_add(1));
+    remove_dead_ends(&mut maze, config.braid, &mut braid_rng);
+    add_loops(&mut maze, config.loops, &mut braid_rng);
 
-fn draw_text(framebuffer: &mut Framebuffer, x: usize, y: usize, text: &str, scale: usize) {
-    for (i, ch) in text.chars().enumerate() {
-        draw_char(framebuffer, x + i * (6 * scale), y, ch, scale);  
+    if config.keys > 0 {
+        let mut key_rng = Rng::new(seed.wrapping_add(2));
+        let placed = place_keys(&mut maze, config.keys, &mut key_rng);
+        if placed < config.keys {
+            eprintln!("Only {} of {} keys fit in this maze", placed, config.keys);
+        }
     }
-}
 
+    let mut coin_rng = Rng::new(seed.wrapping_add(3));
+    scatter_coins(&mut maze, config.coins, &mut coin_rng);
 
+    let mut enemy_rng = Rng::new(seed.wrapping_add(4));
+    spawn_enemies(&mut maze, config.enemies, &mut enemy_rng);
 
-fn wait_for_enter(window: &mut Window, framebuffer: &mut Framebuffer) {
-    framebuffer.clear();
-    framebuffer.set_current_color(Color::new(255, 255, 255)); 
-    draw_text(framebuffer, 100, 200, "Press Enter to Start", 2); 
-
-    window
-        .update_with_buffer(framebuffer.get_buffer(), framebuffer.width, framebuffer.height)
-        .unwrap();
-
-    while window.is_open() && !window.is_key_down(Key::Enter) {
-        framebuffer.clear();
-        framebuffer.set_current_color(Color::new(255, 255, 255)); 
-        draw_text(framebuffer, 100, 200, "Press Enter to Start", 2);  
-
-        window
-            .update_with_buffer(framebuffer.get_buffer(), framebuffer.width, framebuffer.height)
-            .unwrap();
-
-        std::thread::sleep(Duration::from_millis(100));
+    if let Err(err) = validate_maze(&maze) {
+        eprintln!("Generated maze is invalid: {}", err);
+        std::process::exit(1);
     }
+    Level::from_maze("Random", maze)
 }
 
 fn main() {
-    let python_script = "python";
-    let script_path = "maze.py";
-    let args = ["10", "10"];
-
-    let output = Command::new(python_script)
-        .arg(script_path)
-        .args(&args)
-        .output()
-        .expect("Failed to execute script");
-
-    if !output.status.success() {
-        panic!("Failed to generate maze: {:?}", output);
-    }
+    let config = Config::from_args().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let seed = config.seed.unwrap_or_else(random_seed);
+    let preset_level = config.maze.as_ref().map(|path| {
+        load_level(path).unwrap_or_else(|err| {
+            eprintln!("Failed to load level: {}", err);
+            std::process::exit(1);
+        })
+    });
 
     let window_width = 800;
     let window_height = 600;
@@ -432,48 +643,91 @@ fn main() {
     let framebuffer_width = block_size * 31;
     let framebuffer_height = block_size * 21;
 
-    let frame_delay = Duration::from_millis(16);
     let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
 
     let mut window = Window::new(
-        "Maze 2D/3D Taylosr’s Version",
+        "Maze 2D/3D Taylor’s Version",
         window_width,
         window_height,
         WindowOptions::default(),
     )
     .unwrap();
+    // Unos 60 cuadros por segundo; la simulación no depende de este tope
+    window.limit_update_rate(Some(Duration::from_micros(16_600)));
 
     framebuffer.set_background_color(Color::new(0, 0, 0));
 
-    wait_for_enter(&mut window, &mut framebuffer);
-
-    let maze = load_maze("./maze.txt");
+    // Con --maze se juega ese nivel directamente; si no, se elige en el menú
+    let mut level = match preset_level {
+        Some(level) => level,
+        None => {
+            let mut levels = load_levels(LEVELS_DIR);
+            levels.push(generate_level(&config, seed));
+            match select_level(&mut window, &mut framebuffer, &levels) {
+                Some(index) => levels.swap_remove(index),
+                None => return,
+            }
+        }
+    };
+    let mut world = World::new(std::mem::take(&mut level.maze), block_size, seed.wrapping_add(5));
 
-    let player_start = find_player_start(&maze).unwrap_or(Vec2::new(1.0, 1.0));
-    let mut player = Player {
-        pos: player_start * 30.0,
-        a: PI / 3.0,
-        fov: PI / 3.0,
+    let window_title = match &level.author {
+        Some(author) => format!("Maze 2D/3D Taylor’s Version - {} ({})", level.name, author),
+        None => format!("Maze 2D/3D Taylor’s Version - {}", level.name),
     };
+    window.set_title(&window_title);
+
+    // El laberinto ya fue validado, así que siempre hay una `p`
+    let player_start = find_player_start(&world.maze).expect("maze has no player start");
+    let spawn_pos = (player_start + Vec2::new(0.5, 0.5)) * block_size as f32;
+    let mut player = Player::new(spawn_pos.x, spawn_pos.y, level.spawn_angle, config.fov.to_radians());
+    player.head_bob = config.head_bob;
 
-    let audio_player = AudioPlayer::new("fff.wav");
+    let textures = Textures::load(&level).unwrap_or_else(|err| {
+        eprintln!("Failed to load texture: {}", err);
+        std::process::exit(1);
+    });
+
+    let audio_player = AudioPlayer::new(&level.music);
     audio_player.play(); 
 
-    let wall_texture_1 = load_texture("Brick_20-128x128.png");
-    let wall_texture_2 = load_texture("Brick_12-128x128.png");
-    let wall_texture_3 = load_texture("Brick_02-128x128.png");
+    let key_textures: HashMap<char, Texture> = KEY_ORDER
+        .iter()
+        .map(|&key| (key, key_texture(key_color(key))))
+        .collect();
 
-    let texture_width = 128;
-    let texture_height = 128;
-    
     let mut mode = "2D";
     let mut m_was_down = false;
-    let mut last_mouse_pos = None;
+    let mut input = InputState::default();
 
     let mut last_time = Instant::now();
     let mut frames = 0;
     let mut fps = 0.0;
 
+    // Cada vez que se enciende la pista se suman segundos al tiempo de la partida
+    const HINT_PENALTY_SECONDS: f32 = 10.0;
+    // Si un enemigo atrapa al jugador, vuelve al inicio con esta penalización
+    const CAUGHT_PENALTY_SECONDS: f32 = 15.0;
+    let goal = find_cell(&world.maze, 'g');
+    let goal_sprite_texture = goal_texture();
+    let coin_sprite_texture = coin_texture();
+    let enemy_sprite_texture = enemy_texture();
+    let mut zbuffer = vec![f32::INFINITY; framebuffer_width];
+    let mut show_hint = false;
+    let mut h_was_down = false;
+    let mut penalty = 0.0;
+    let run_start = Instant::now();
+    let mut finish_time: Option<f32> = None;
+
+    // La simulación avanza en ticks fijos de TICK segundos, sin importar
+    // cuánto tarde cada cuadro en dibujarse
+    const TICK: f32 = 1.0 / 60.0;
+    // Tras una pausa larga (p. ej. arrastrar la ventana) no se recupera todo
+    const MAX_FRAME_TIME: f32 = 0.25;
+    let mut accumulator = 0.0;
+    let mut previous_tick = Instant::now();
+    let mut previous_player = player.clone();
+
     while window.is_open() && !window.is_key_down(Key::Escape) {
         framebuffer.clear();
 
@@ -483,46 +737,85 @@ fn main() {
         }
         m_was_down = m_is_down;
 
-        process_events(&window, &mut player, &maze, block_size, &mut last_mouse_pos, &audio_player);
+        let h_is_down = window.is_key_down(Key::H);
+        if h_is_down && !h_was_down {
+            show_hint = !show_hint;
+            if show_hint && finish_time.is_none() {
+                penalty += HINT_PENALTY_SECONDS;
+            }
+        }
+        h_was_down = h_is_down;
+
+        let now = Instant::now();
+        accumulator += now.duration_since(previous_tick).as_secs_f32().min(MAX_FRAME_TIME);
+        previous_tick = now;
+
+        while accumulator >= TICK {
+            accumulator -= TICK;
+            previous_player = player.clone();
+
+            process_events(&window, &mut player, &mut world, block_size, &mut input, &audio_player, TICK);
+            world.doors.update(TICK);
+
+            if finish_time.is_none() && world.enemies.update(&world.maze, &world.doors, &player, block_size, TICK) {
+                penalty += CAUGHT_PENALTY_SECONDS;
+                player.pos = spawn_pos;
+                previous_player.pos = spawn_pos;
+                world.enemies.reset(block_size);
+            }
+        }
+
+        let run_time = run_start.elapsed().as_secs_f32() + penalty;
+        let player_cell = (
+            (player.pos.x / block_size as f32) as usize,
+            (player.pos.y / block_size as f32) as usize,
+        );
+        if finish_time.is_none() && Some(player_cell) == goal {
+            finish_time = Some(run_time);
+        }
+
+        // Se dibuja entre el tick anterior y el actual para que el movimiento
+        // no vaya a saltos cuando los cuadros y los ticks no coinciden
+        let view = previous_player.lerp(&player, accumulator / TICK);
+
+        let hint_path = match goal {
+            Some(goal) if show_hint => solve(&world.maze, player_cell, goal, config.hint_solver).unwrap_or_default(),
+            _ => Vec::new(),
+        };
 
         if mode == "2D" {
-            render2d(
-                &mut framebuffer,
-                &player,
-                &maze,
-                block_size,
-                &wall_texture_1,
-                &wall_texture_2,
-                &wall_texture_3,
-                texture_width,
-                texture_height,
-            );
+            render2d(&mut framebuffer, &view, &world, &textures, block_size, &hint_path);
         } else {
-            render3d(
-                &mut framebuffer,
-                &player,
-                &maze,
-                block_size,
-                &wall_texture_1,
-                &wall_texture_2,
-                &wall_texture_3,
-                texture_width,
-                texture_height,
-            );
+            render3d(&mut framebuffer, &view, &world, &textures, block_size, &level.fog, &mut zbuffer);
+
+            let cell_center = |(col, row): Cell| Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * block_size as f32;
+            let mut sprites: Vec<Sprite> = goal
+                .into_iter()
+                .map(|cell| Sprite {
+                    pos: cell_center(cell),
+                    texture: &goal_sprite_texture,
+                    scale: 0.5,
+                })
+                .collect();
+            sprites.extend(world.keys.items().iter().map(|&(cell, key)| Sprite {
+                pos: cell_center(cell),
+                texture: &key_textures[&key],
+                scale: 0.3,
+            }));
+            sprites.extend(world.coins.items().iter().map(|&cell| Sprite {
+                pos: cell_center(cell),
+                texture: &coin_sprite_texture,
+                scale: 0.25,
+            }));
+            sprites.extend(world.enemies.iter().map(|enemy| Sprite {
+                pos: enemy.pos,
+                texture: &enemy_sprite_texture,
+                scale: 0.7,
+            }));
+            render_sprites(&mut framebuffer, &view, &mut sprites, &zbuffer, block_size, &level.fog);
         }
 
-        let framebuffer_width = framebuffer.width;
-        let framebuffer_height = framebuffer.height;
-
-        render_minimap(
-            &mut framebuffer,
-            &player,
-            &maze,
-            framebuffer_width,
-            framebuffer_height,
-            200,                 
-            block_size,          
-        );
+        render_minimap(&mut framebuffer, &view, &world, 200, block_size, &hint_path);
 
         frames += 1;
         let now = Instant::now();
@@ -533,13 +826,22 @@ fn main() {
             last_time = now;
         }
 
-        render_fps(&mut framebuffer, fps);
+        render_fps(&mut framebuffer, fps, seed);
+        render_timer(&mut framebuffer, finish_time.unwrap_or(run_time), level.par_time);
+        if !world.keys.held().is_empty() || !world.keys.items().is_empty() {
+            render_keys(&mut framebuffer, world.keys.held());
+        }
+        if world.coins.total() > 0 {
+            render_score(&mut framebuffer, &world.coins);
+        }
+        render_stamina(&mut framebuffer, &view);
+        if let Some(seconds) = finish_time {
+            render_victory(&mut framebuffer, seconds, &world.coins);
+        }
 
         window
-            .update_with_buffer(framebuffer.get_buffer(), framebuffer_width, framebuffer_height)
+            .update_with_buffer(framebuffer.get_buffer(), framebuffer.width, framebuffer.height)
             .unwrap();
-
-        std::thread::sleep(frame_delay);
     }
     audio_player.stop();
 }
@@ -1,12 +1,509 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::audio_player::is_playable;
+use crate::color::Color;
+use crate::doors::door_side;
+use crate::lighting::Fog;
+use crate::collectibles::COIN;
+use crate::enemies::ENEMY;
+use crate::keys::is_solvable;
+use crate::texture::Texture;
+use crate::generator::{MazeGenerator, RecursiveBacktracker};
+use crate::solver::{solve, Strategy};
 
-pub fn load_maze(filename: &str) -> Vec<Vec<char>> {
-    let file = File::open(filename).unwrap();
-    let reader = BufReader::new(file);
+// Líneas y columnas empiezan en 1, como en un editor de texto
+#[derive(Debug)]
+pub enum MazeError {
+    Io(String, io::Error),
+    Empty,
+    RaggedRow { line: usize, expected: usize, found: usize },
+    UnknownCharacter { line: usize, column: usize, character: char },
+    MissingStart,
+    MissingGoal,
+    DuplicateStart { line: usize, column: usize },
+    DuplicateGoal { line: usize, column: usize },
+    OpenBorder { line: usize, column: usize },
+    UnreachableGoal,
+    MisplacedDoor { line: usize, column: usize },
+    LockedGoal,
+    InvalidHeader { line: usize, message: String },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeError::Io(path, err) => write!(f, "{}: {}", path, err),
+            MazeError::Empty => write!(f, "maze is empty"),
+            MazeError::RaggedRow { line, expected, found } => write!(
+                f,
+                "line {}: expected {} columns, found {}",
+                line, expected, found
+            ),
+            MazeError::UnknownCharacter { line, column, character } => write!(
+                f,
+                "line {}, column {}: unknown character '{}'",
+                line, column, character
+            ),
+            MazeError::MissingStart => write!(f, "maze has no player start 'p'"),
+            MazeError::MissingGoal => write!(f, "maze has no goal 'g'"),
+            MazeError::DuplicateStart { line, column } => write!(
+                f,
+                "line {}, column {}: second player start 'p'",
+                line, column
+            ),
+            MazeError::DuplicateGoal { line, column } => {
+                write!(f, "line {}, column {}: second goal 'g'", line, column)
+            }
+            MazeError::OpenBorder { line, column } => write!(
+                f,
+                "line {}, column {}: border must be a wall",
+                line, column
+            ),
+            MazeError::UnreachableGoal => write!(f, "goal 'g' cannot be reached from 'p'"),
+            MazeError::MisplacedDoor { line, column } => write!(
+                f,
+                "line {}, column {}: door 'D' must sit between two open cells",
+                line, column
+            ),
+            MazeError::LockedGoal => {
+                write!(f, "goal 'g' is behind a locked door whose key cannot be reached")
+            }
+            MazeError::InvalidHeader { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+impl MazeError {
+    // Corrige los números de línea cuando la rejilla no empieza en la línea 1
+    fn offset_lines(self, offset: usize) -> Self {
+        match self {
+            MazeError::RaggedRow { line, expected, found } => MazeError::RaggedRow {
+                line: line + offset,
+                expected,
+                found,
+            },
+            MazeError::UnknownCharacter { line, column, character } => {
+                MazeError::UnknownCharacter { line: line + offset, column, character }
+            }
+            MazeError::DuplicateStart { line, column } => {
+                MazeError::DuplicateStart { line: line + offset, column }
+            }
+            MazeError::DuplicateGoal { line, column } => {
+                MazeError::DuplicateGoal { line: line + offset, column }
+            }
+            MazeError::OpenBorder { line, column } => {
+                MazeError::OpenBorder { line: line + offset, column }
+            }
+            MazeError::MisplacedDoor { line, column } => {
+                MazeError::MisplacedDoor { line: line + offset, column }
+            }
+            other => other,
+        }
+    }
+}
+
+// El techo puede ser una imagen o un color liso (`#rrggbb`)
+pub enum Ceiling {
+    Color(Color),
+    Texture(String),
+}
+
+// Un nivel: la rejilla más los datos que antes estaban fijos en main()
+pub struct Level {
+    pub name: String,
+    pub author: Option<String>,
+    pub textures: HashMap<char, String>,
+    pub floor: String,
+    pub ceiling: Ceiling,
+    pub fog: Fog,
+    // Ángulo inicial del jugador, en radianes
+    pub spawn_angle: f32,
+    // Tiempo objetivo en segundos
+    pub par_time: Option<f32>,
+    pub music: String,
+    pub maze: Vec<Vec<char>>,
+}
+
+impl Level {
+    pub fn from_maze(name: &str, maze: Vec<Vec<char>>) -> Self {
+        let mut textures = HashMap::new();
+        textures.insert('+', String::from("Brick_20-128x128.png"));
+        textures.insert('-', String::from("Brick_12-128x128.png"));
+        textures.insert('|', String::from("Brick_02-128x128.png"));
+
+        Level {
+            name: name.to_string(),
+            author: None,
+            textures,
+            floor: String::from("Suelo.png"),
+            ceiling: Ceiling::Color(Color::new(40, 40, 60)),
+            fog: Fog::new(Color::new(0, 0, 0), 0.08),
+            spawn_angle: PI / 3.0,
+            par_time: None,
+            music: String::from("fff.wav"),
+            maze,
+        }
+    }
+
+    pub fn texture_for(&self, cell: char) -> &str {
+        self.textures.get(&cell).map(|path| path.as_str()).unwrap_or("Brick_20-128x128.png")
+    }
+}
+
+// Carga un nivel. Acepta el formato con cabecera:
+//
+//     name = Primer nivel
+//     author = Diego
+//     music = win.wav
+//     par_time = 90
+//     spawn_angle = 60
+//     texture.+ = Brick_20-128x128.png
+//     floor = Suelo.png
+//     ceiling = #28283c
+//     fog_color = #000000
+//     fog_density = 0.08
+//
+//     [maze]
+//     +--+--+
+//     ...
+//
+// y también los `.txt` de siempre, que son sólo la rejilla.
+pub fn load_level(filename: &str) -> Result<Level, MazeError> {
+    let text = fs::read_to_string(filename).map_err(|err| MazeError::Io(filename.to_string(), err))?;
+    let lines: Vec<&str> = text.lines().collect();
+
+    let name = Path::new(filename)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| filename.to_string());
+
+    let section = lines.iter().position(|line| line.trim() == "[maze]");
+    let Some(section) = section else {
+        let maze = parse_maze(&lines)?;
+        return Ok(Level::from_maze(&name, maze));
+    };
+
+    let maze = parse_maze(&lines[section + 1..]).map_err(|err| err.offset_lines(section + 1))?;
+    let mut level = Level::from_maze(&name, maze);
+
+    for (index, line) in lines[..section].iter().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |message: String| MazeError::InvalidHeader { line: line_number, message };
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("expected 'key = value', found '{}'", line)))?;
+        let (key, value) = (key.trim(), value.trim());
+
+        // Las texturas y la música se revisan aquí, para que un error de tipeo o
+        // un archivo equivocado no haga fallar la carga de la imagen o el sonido más tarde
+        let asset = |path: &str| {
+            if Path::new(path).is_file() {
+                Ok(path.to_string())
+            } else {
+                Err(invalid(format!("{}: file '{}' not found", key, path)))
+            }
+        };
+        let image = |path: &str| {
+            let path = asset(path)?;
+            match Texture::load(&path) {
+                Ok(_) => Ok(path),
+                Err(err) => Err(invalid(format!("{}: cannot read image '{}': {}", key, path, err))),
+            }
+        };
+        let sound = |path: &str| {
+            let path = asset(path)?;
+            if is_playable(&path) {
+                Ok(path)
+            } else {
+                Err(invalid(format!("{}: '{}' is not a supported audio file", key, path)))
+            }
+        };
+
+        match key {
+            "name" => level.name = value.to_string(),
+            "author" => level.author = Some(value.to_string()),
+            "music" => level.music = sound(value)?,
+            "floor" => level.floor = image(value)?,
+            "ceiling" => {
+                level.ceiling = if value.starts_with('#') {
+                    let color = parse_color(value)
+                        .ok_or_else(|| invalid(format!("invalid ceiling color '{}'", value)))?;
+                    Ceiling::Color(color)
+                } else {
+                    Ceiling::Texture(image(value)?)
+                };
+            }
+            "fog_color" => {
+                level.fog.color =
+                    parse_color(value).ok_or_else(|| invalid(format!("invalid fog_color '{}'", value)))?;
+            }
+            "fog_density" => {
+                let density: f32 = value
+                    .parse()
+                    .ok()
+                    .filter(|density: &f32| *density >= 0.0)
+                    .ok_or_else(|| invalid(format!("invalid fog_density '{}'", value)))?;
+                level.fog.density = density;
+            }
+            "par_time" => {
+                let seconds: f32 = value
+                    .parse()
+                    .map_err(|_| invalid(format!("invalid par_time '{}'", value)))?;
+                level.par_time = Some(seconds);
+            }
+            "spawn_angle" => {
+                let degrees: f32 = value
+                    .parse()
+                    .map_err(|_| invalid(format!("invalid spawn_angle '{}'", value)))?;
+                level.spawn_angle = degrees.to_radians();
+            }
+            _ => {
+                let mut cells = key.strip_prefix("texture.").unwrap_or("").chars();
+                match (cells.next(), cells.next()) {
+                    (Some(cell), None) if is_wall(cell) || is_door(cell) => {
+                        level.textures.insert(cell, image(value)?);
+                    }
+                    _ => return Err(invalid(format!("unknown key '{}'", key))),
+                }
+            }
+        }
+    }
+
+    Ok(level)
+}
+
+// Colores en la forma `#rrggbb`
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+}
+
+fn parse_maze(lines: &[&str]) -> Result<Vec<Vec<char>>, MazeError> {
+    let mut maze: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+
+    // Los archivos de maze.py terminan con una línea vacía
+    while maze.last().is_some_and(|line| line.is_empty()) {
+        maze.pop();
+    }
+
+    validate_maze(&maze)?;
+    Ok(maze)
+}
+
+pub fn is_wall(cell: char) -> bool {
+    matches!(cell, '+' | '-' | '|')
+}
+
+// Casillas por las que se puede caminar: el piso, el inicio, la meta, las
+// llaves y monedas tiradas en el piso y el punto de partida de los enemigos
+pub fn is_walkable(cell: char) -> bool {
+    matches!(cell, ' ' | 'p' | 'g') || is_key(cell) || cell == COIN || cell == ENEMY
+}
+
+pub const DOOR: char = 'D';
+
+// Las puertas con cerradura son la mayúscula de la llave que las abre
+pub fn is_door(cell: char) -> bool {
+    cell == DOOR || is_locked_door(cell)
+}
+
+pub fn is_key(cell: char) -> bool {
+    matches!(cell, 'r' | 'b' | 'y')
+}
+
+pub fn is_locked_door(cell: char) -> bool {
+    matches!(cell, 'R' | 'B' | 'Y')
+}
+
+pub fn key_for_door(door: char) -> Option<char> {
+    is_locked_door(door).then(|| door.to_ascii_lowercase())
+}
+
+// Casillas que un camino puede cruzar si se abren las puertas
+pub fn is_passable(cell: char) -> bool {
+    is_walkable(cell) || is_door(cell)
+}
+
+// Comprueba que la rejilla sea rectangular, cerrada por paredes y con un
+// único inicio y una única meta alcanzable, para que `cast_ray` y
+// `process_events` puedan indexar sin salirse.
+pub fn validate_maze(maze: &[Vec<char>]) -> Result<(), MazeError> {
+    let width = maze.first().map(|line| line.len()).unwrap_or(0);
+    if width == 0 {
+        return Err(MazeError::Empty);
+    }
+
+    let mut start = None;
+    let mut goal = None;
+
+    for (row, line) in maze.iter().enumerate() {
+        if line.len() != width {
+            return Err(MazeError::RaggedRow {
+                line: row + 1,
+                expected: width,
+                found: line.len(),
+            });
+        }
+
+        for (col, &cell) in line.iter().enumerate() {
+            let (line, column) = (row + 1, col + 1);
+
+            if !is_wall(cell) && !is_passable(cell) {
+                return Err(MazeError::UnknownCharacter { line, column, character: cell });
+            }
+
+            let on_border = row == 0 || col == 0 || row + 1 == maze.len() || col + 1 == width;
+            if on_border && !is_wall(cell) {
+                return Err(MazeError::OpenBorder { line, column });
+            }
+
+            if is_door(cell) && door_side(maze, (col, row)).is_none() {
+                return Err(MazeError::MisplacedDoor { line, column });
+            }
+
+            if cell == 'p' {
+                if start.is_some() {
+                    return Err(MazeError::DuplicateStart { line, column });
+                }
+                start = Some((col, row));
+            }
+            if cell == 'g' {
+                if goal.is_some() {
+                    return Err(MazeError::DuplicateGoal { line, column });
+                }
+                goal = Some((col, row));
+            }
+        }
+    }
+
+    let start = start.ok_or(MazeError::MissingStart)?;
+    let goal = goal.ok_or(MazeError::MissingGoal)?;
+    if solve(maze, start, goal, Strategy::Bfs).is_none() {
+        return Err(MazeError::UnreachableGoal);
+    }
+    if !is_solvable(maze, start, goal) {
+        return Err(MazeError::LockedGoal);
+    }
+
+    Ok(())
+}
+
+// Genera un laberinto con el mismo formato que producía maze.py
+// (celdas de 3 caracteres, `+--`, `|  `, `p` arriba a la izquierda y `g`
+// abajo a la derecha) usando backtracking recursivo.
+pub fn generate(width: usize, height: usize, seed: u64) -> Vec<Vec<char>> {
+    RecursiveBacktracker.generate(width, height, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: [&str; 5] = [
+        "+--+--+",
+        "|p    |",
+        "+  +  +",
+        "|    g|",
+        "+--+--+",
+    ];
+
+    fn grid(lines: &[&str]) -> Vec<Vec<char>> {
+        lines.iter().map(|line| line.chars().collect()).collect()
+    }
+
+    // Cada prueba escribe su propio archivo para poder correr en paralelo
+    fn load_error(name: &str, text: &str) -> MazeError {
+        let path = std::env::temp_dir().join(format!("pry1-{}-{}.txt", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        let result = load_level(&path.to_string_lossy());
+        fs::remove_file(&path).unwrap();
+        result.err().expect("level should not load")
+    }
+
+    #[test]
+    fn accepts_valid_maze() {
+        assert!(validate_maze(&grid(&VALID)).is_ok());
+    }
+
+    #[test]
+    fn rejects_ragged_row() {
+        let mut lines = VALID;
+        lines[2] = "+  +  ";
+        let err = validate_maze(&grid(&lines)).unwrap_err();
+        assert!(matches!(err, MazeError::RaggedRow { line: 3, expected: 7, found: 6 }));
+    }
+
+    #[test]
+    fn rejects_open_border() {
+        let mut lines = VALID;
+        lines[0] = "+--+  +";
+        let err = validate_maze(&grid(&lines)).unwrap_err();
+        assert!(matches!(err, MazeError::OpenBorder { line: 1, column: 5 }));
+    }
+
+    #[test]
+    fn rejects_missing_start_and_goal() {
+        let mut lines = VALID;
+        lines[1] = "|     |";
+        assert!(matches!(validate_maze(&grid(&lines)), Err(MazeError::MissingStart)));
+
+        let mut lines = VALID;
+        lines[3] = "|     |";
+        assert!(matches!(validate_maze(&grid(&lines)), Err(MazeError::MissingGoal)));
+    }
+
+    #[test]
+    fn rejects_duplicate_start_and_goal() {
+        let mut lines = VALID;
+        lines[3] = "|p   g|";
+        let err = validate_maze(&grid(&lines)).unwrap_err();
+        assert!(matches!(err, MazeError::DuplicateStart { line: 4, column: 2 }));
+
+        let mut lines = VALID;
+        lines[1] = "|p  g |";
+        let err = validate_maze(&grid(&lines)).unwrap_err();
+        assert!(matches!(err, MazeError::DuplicateGoal { line: 4, column: 6 }));
+    }
+
+    #[test]
+    fn rejects_unreachable_goal() {
+        let lines = [
+            "+--+--+",
+            "|p |  |",
+            "+--+  +",
+            "|    g|",
+            "+--+--+",
+        ];
+        assert!(matches!(validate_maze(&grid(&lines)), Err(MazeError::UnreachableGoal)));
+    }
+
+    #[test]
+    fn reports_file_lines_around_header() {
+        // La cabecera ocupa las líneas 1 a 4 y la rejilla empieza en la 5
+        let header = "name = Prueba\nbogus\n\n[maze]\n";
+        let err = load_error("header", &format!("{}{}\n", header, VALID.join("\n")));
+        assert!(matches!(err, MazeError::InvalidHeader { line: 2, .. }));
 
-    reader
-        .lines()
-        .map(|line| line.unwrap().chars().collect())
-        .collect()
+        let header = "name = Prueba\n\n[maze]\n";
+        let mut lines = VALID;
+        lines[2] = "+  +  ";
+        let err = load_error("offset", &format!("{}{}\n", header, lines.join("\n")));
+        assert!(matches!(err, MazeError::RaggedRow { line: 6, .. }));
+    }
 }
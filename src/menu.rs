@@ -0,0 +1,143 @@
+use minifb::{Key, Window};
+use std::fs;
+use std::time::Duration;
+use crate::framebuffer::Framebuffer;
+use crate::color::Color;
+use crate::font::{draw_text, text_width};
+use crate::maze::{load_level, Level};
+use crate::minimap::{draw_maze_thumbnail, thumbnail_scale};
+
+pub const LEVELS_DIR: &str = "levels";
+
+const CARD_SIZE: usize = 160;
+const CARD_GAP: usize = 24;
+const CARDS_PER_PAGE: usize = 4;
+
+// Carga los niveles válidos de la carpeta, ordenados por nombre de archivo.
+// Los que no se pueden leer se avisan por consola y se omiten.
+pub fn load_levels(dir: &str) -> Vec<Level> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut levels = Vec::new();
+    for path in paths {
+        let path = path.to_string_lossy();
+        match load_level(&path) {
+            Ok(level) => levels.push(level),
+            Err(err) => eprintln!("Skipping level {}: {}", path, err),
+        }
+    }
+    levels
+}
+
+// Pantalla de selección: izquierda/derecha para elegir, Enter para jugar.
+// Devuelve None si se cierra la ventana o se pulsa Escape.
+pub fn select_level(window: &mut Window, framebuffer: &mut Framebuffer, levels: &[Level]) -> Option<usize> {
+    let mut selected = 0;
+    let mut left_was_down = false;
+    let mut right_was_down = false;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let left_is_down = window.is_key_down(Key::Left) || window.is_key_down(Key::Up);
+        let right_is_down = window.is_key_down(Key::Right) || window.is_key_down(Key::Down);
+
+        if left_is_down && !left_was_down && selected > 0 {
+            selected -= 1;
+        }
+        if right_is_down && !right_was_down && selected + 1 < levels.len() {
+            selected += 1;
+        }
+        left_was_down = left_is_down;
+        right_was_down = right_is_down;
+
+        if window.is_key_down(Key::Enter) {
+            return Some(selected);
+        }
+
+        render_menu(framebuffer, levels, selected);
+
+        window
+            .update_with_buffer(framebuffer.get_buffer(), framebuffer.width, framebuffer.height)
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(16));
+    }
+
+    None
+}
+
+fn render_menu(framebuffer: &mut Framebuffer, levels: &[Level], selected: usize) {
+    framebuffer.clear();
+
+    framebuffer.set_current_color(Color::new(255, 255, 255));
+    draw_centered(framebuffer, 30, "SELECT LEVEL", 3);
+
+    // Página de tarjetas que siempre incluye la seleccionada
+    let first = selected.saturating_sub(CARDS_PER_PAGE - 1);
+    let visible = &levels[first..levels.len().min(first + CARDS_PER_PAGE)];
+    let row_width = visible.len() * CARD_SIZE + visible.len().saturating_sub(1) * CARD_GAP;
+    let start_x = framebuffer.width.saturating_sub(row_width) / 2;
+    let card_y = 100;
+
+    for (offset, level) in visible.iter().enumerate() {
+        let x = start_x + offset * (CARD_SIZE + CARD_GAP);
+        let is_selected = first + offset == selected;
+
+        if is_selected {
+            draw_frame(framebuffer, x, card_y, CARD_SIZE, Color::new(255, 255, 0));
+        }
+
+        let scale = thumbnail_scale(&level.maze, CARD_SIZE - 10);
+        draw_maze_thumbnail(framebuffer, &level.maze, x + 5, card_y + 5, scale);
+
+        let max_chars = CARD_SIZE / 12;
+        let name: String = level.name.chars().take(max_chars).collect();
+        let color = if is_selected { Color::new(255, 255, 0) } else { Color::new(200, 200, 200) };
+        framebuffer.set_current_color(color);
+        draw_text(framebuffer, x + (CARD_SIZE - text_width(&name, 2)) / 2, card_y + CARD_SIZE + 12, &name, 2);
+    }
+
+    let level = &levels[selected];
+    framebuffer.set_current_color(Color::new(200, 200, 200));
+    let mut info_y = card_y + CARD_SIZE + 60;
+    if let Some(author) = &level.author {
+        draw_centered(framebuffer, info_y, &format!("BY {}", author), 2);
+        info_y += 24;
+    }
+    if let Some(par) = level.par_time {
+        draw_centered(framebuffer, info_y, &format!("PAR: {:.0}", par), 2);
+    }
+
+    framebuffer.set_current_color(Color::new(255, 255, 255));
+    draw_centered(
+        framebuffer,
+        framebuffer.height - 40,
+        "< > TO CHOOSE - ENTER TO PLAY",
+        2,
+    );
+}
+
+fn draw_centered(framebuffer: &mut Framebuffer, y: usize, text: &str, scale: usize) {
+    let x = framebuffer.width.saturating_sub(text_width(text, scale)) / 2;
+    draw_text(framebuffer, x, y, text, scale);
+}
+
+fn draw_frame(framebuffer: &mut Framebuffer, x: usize, y: usize, size: usize, color: Color) {
+    let color = color.to_hex();
+    for i in 0..size {
+        for t in 0..3 {
+            framebuffer.point(x + i, y + t, color);
+            framebuffer.point(x + i, y + size - 1 - t, color);
+            framebuffer.point(x + t, y + i, color);
+            framebuffer.point(x + size - 1 - t, y + i, color);
+        }
+    }
+}
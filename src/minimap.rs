@@ -0,0 +1,47 @@
+use crate::framebuffer::Framebuffer;
+use crate::collectibles::{coin_color, COIN};
+use crate::color::Color;
+use crate::enemies::{enemy_color, ENEMY};
+use crate::keys::key_color;
+use crate::maze::{is_key, is_locked_door};
+
+// Dibuja la rejilla completa con un cuadro de `scale` píxeles por casilla.
+// La usan el minimapa del juego y las miniaturas del menú de niveles.
+pub fn draw_maze_thumbnail(
+    framebuffer: &mut Framebuffer,
+    maze: &[Vec<char>],
+    origin_x: usize,
+    origin_y: usize,
+    scale: usize,
+) {
+    for (row_index, row) in maze.iter().enumerate() {
+        for (col_index, &cell) in row.iter().enumerate() {
+            let color = match cell {
+                '+' | '-' | '|' => Color::new(100, 100, 100),
+                'D' => Color::new(140, 95, 50),
+                c if is_key(c) || is_locked_door(c) => key_color(c),
+                COIN => coin_color(),
+                ENEMY => enemy_color(),
+                'p' => Color::new(0, 255, 0),
+                'g' => Color::new(255, 0, 0),
+                _ => Color::new(200, 200, 200),
+            };
+
+            framebuffer.set_current_color(color);
+
+            let x0 = origin_x + col_index * scale;
+            let y0 = origin_y + row_index * scale;
+            for x in 0..scale {
+                for y in 0..scale {
+                    framebuffer.point(x0 + x, y0 + y, framebuffer.current_color.to_hex());
+                }
+            }
+        }
+    }
+}
+
+// Tamaño de casilla para que la rejilla quepa en un cuadro de `size` píxeles
+pub fn thumbnail_scale(maze: &[Vec<char>], size: usize) -> usize {
+    let longest = maze.len().max(maze.first().map(|row| row.len()).unwrap_or(0));
+    (size / longest.max(1)).max(1)
+}
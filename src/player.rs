@@ -1,9 +1,51 @@
 use nalgebra_glm::Vec2;
+use std::f32::consts::PI;
+use crate::solver::Cell;
 
+// Radio del jugador, en casillas
+pub const PLAYER_RADIUS: f32 = 0.2;
+
+// Altura de los ojos de pie, en casillas (media pared)
+const STANDING_EYE_HEIGHT: f32 = 0.5;
+// Cuánto bajan los ojos al agacharse del todo, en casillas
+const CROUCH_DROP: f32 = 0.2;
+// Fracción de la agachada que se completa por segundo
+const CROUCH_SPEED: f32 = 6.0;
+// Velocidad inicial del salto y gravedad, en casillas por segundo (y por segundo²)
+const JUMP_SPEED: f32 = 2.5;
+const GRAVITY: f32 = 9.0;
+// Límite de la inclinación vertical, en radianes
+pub const MAX_PITCH: f32 = 0.6;
+// Corriendo se avanza este múltiplo de la velocidad normal
+pub const SPRINT_FACTOR: f32 = 1.6;
+// Fracción de la resistencia que se gasta y se recupera por segundo
+const STAMINA_DRAIN: f32 = 0.3;
+const STAMINA_REGEN: f32 = 0.2;
+// Tras agotarse no se vuelve a correr hasta recuperar esto
+const STAMINA_RECOVERY: f32 = 0.3;
+// Casillas recorridas entre un paso y el siguiente
+const STEP_LENGTH: f32 = 3.0;
+// Altura del balanceo de la cabeza, en fracción de la altura de la pantalla
+const BOB_AMPLITUDE: f32 = 0.012;
+
+#[derive(Clone)]
 pub struct Player {
     pub pos: Vec2,
     pub a: f32,  
-    pub fov: f32 
+    pub fov: f32,
+    // Inclinación vertical de la vista; positiva mirando hacia arriba
+    pub pitch: f32,
+    // Altura de los pies sobre el piso durante un salto, en casillas
+    pub jump: f32,
+    vertical_speed: f32,
+    // 0.0 de pie, 1.0 agachado
+    pub crouch: f32,
+    // Resistencia para correr, de 0.0 a 1.0
+    pub stamina: f32,
+    pub exhausted: bool,
+    // Casillas recorridas; marca los pasos y el balanceo de la cabeza
+    stride: f32,
+    pub head_bob: bool,
 }
 
 impl Player {
@@ -11,7 +53,139 @@ impl Player {
         Player {
             pos: Vec2::new(x, y),
             a: angle,
-            fov
+            fov,
+            pitch: 0.0,
+            jump: 0.0,
+            vertical_speed: 0.0,
+            crouch: 0.0,
+            stamina: 1.0,
+            exhausted: false,
+            stride: 0.0,
+            head_bob: true,
+        }
+    }
+
+    // Distancia a la que el plano de proyección, de `screen_width` píxeles, cubre todo el FOV
+    pub fn projection_plane_distance(&self, screen_width: usize) -> f32 {
+        (screen_width as f32 / 2.0) / (self.fov / 2.0).tan()
+    }
+
+    // Altura de los ojos sobre el piso, en casillas
+    pub fn eye_height(&self) -> f32 {
+        STANDING_EYE_HEIGHT - CROUCH_DROP * self.crouch + self.jump
+    }
+
+    // Fila de pantalla del horizonte: mirar arriba o abajo desplaza toda la
+    // imagen en vertical en lugar de girar la cámara
+    pub fn horizon(&self, screen_height: usize, distance_to_projection_plane: f32) -> f32 {
+        screen_height as f32 / 2.0 + self.pitch.tan() * distance_to_projection_plane + self.bob(screen_height)
+    }
+
+    // Desplazamiento del horizonte por el balanceo: vuelve a cero en cada paso
+    fn bob(&self, screen_height: usize) -> f32 {
+        if !self.head_bob {
+            return 0.0;
+        }
+        (self.stride / STEP_LENGTH * PI).sin().abs() * BOB_AMPLITUDE * screen_height as f32
+    }
+
+    // Se corre sólo de pie y con resistencia que no esté agotada
+    pub fn can_sprint(&self) -> bool {
+        !self.exhausted && self.crouch == 0.0
+    }
+
+    // Gasta resistencia si el jugador corrió de verdad en este tick; si no, la recupera
+    pub fn update_stamina(&mut self, sprinted: bool, dt: f32) {
+        if sprinted {
+            self.stamina = (self.stamina - STAMINA_DRAIN * dt).max(0.0);
+            self.exhausted = self.stamina == 0.0;
+        } else {
+            self.stamina = (self.stamina + STAMINA_REGEN * dt).min(1.0);
+            if self.stamina >= STAMINA_RECOVERY {
+                self.exhausted = false;
+            }
+        }
+    }
+
+    // Suma `distance` casillas caminadas. Devuelve true si se completó un paso.
+    // Quieto, la cabeza vuelve a su altura en el paso más cercano.
+    pub fn walk(&mut self, distance: f32, dt: f32) -> bool {
+        if distance > 0.0 && self.on_ground() {
+            let step = (self.stride / STEP_LENGTH).floor();
+            self.stride += distance;
+            return (self.stride / STEP_LENGTH).floor() > step;
+        }
+
+        let nearest = (self.stride / STEP_LENGTH).round() * STEP_LENGTH;
+        let settle = STEP_LENGTH * 2.0 * dt;
+        self.stride += (nearest - self.stride).clamp(-settle, settle);
+        false
+    }
+
+    pub fn on_ground(&self) -> bool {
+        self.jump <= 0.0
+    }
+
+    // Avanza el salto y la agachada `dt` segundos. Sólo se salta desde el piso.
+    pub fn update_height(&mut self, jumping: bool, crouching: bool, dt: f32) {
+        if jumping && self.on_ground() {
+            self.vertical_speed = JUMP_SPEED;
+        }
+        if !self.on_ground() || self.vertical_speed > 0.0 {
+            self.vertical_speed -= GRAVITY * dt;
+            self.jump += self.vertical_speed * dt;
+            if self.jump <= 0.0 {
+                self.jump = 0.0;
+                self.vertical_speed = 0.0;
+            }
+        }
+
+        let target = if crouching { 1.0 } else { 0.0 };
+        let step = CROUCH_SPEED * dt;
+        self.crouch = if self.crouch < target {
+            (self.crouch + step).min(target)
+        } else {
+            (self.crouch - step).max(target)
+        };
+    }
+
+    // Estado intermedio entre `self` (tick anterior) y `next`, con `t` de 0.0 a 1.0
+    pub fn lerp(&self, next: &Player, t: f32) -> Player {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Player {
+            pos: self.pos + (next.pos - self.pos) * t,
+            a: mix(self.a, next.a),
+            fov: next.fov,
+            pitch: mix(self.pitch, next.pitch),
+            jump: mix(self.jump, next.jump),
+            vertical_speed: next.vertical_speed,
+            crouch: mix(self.crouch, next.crouch),
+            stamina: next.stamina,
+            exhausted: next.exhausted,
+            stride: mix(self.stride, next.stride),
+            head_bob: next.head_bob,
         }
     }
 }
+
+// True si el círculo del jugador centrado en `pos` toca la casilla
+pub fn touches_cell(pos: Vec2, (col, row): Cell, block_size: usize) -> bool {
+    let block = block_size as f32;
+    let radius = PLAYER_RADIUS * block;
+    // Punto de la casilla más cercano al centro
+    let closest = Vec2::new(
+        pos.x.clamp(col as f32 * block, (col + 1) as f32 * block),
+        pos.y.clamp(row as f32 * block, (row + 1) as f32 * block),
+    );
+    (closest - pos).norm() < radius
+}
+
+// Un objeto en el piso ocupa toda su celda del laberinto, los dos caracteres
+// entre paredes, así que se alcanza desde cualquiera de las dos mitades del pasillo
+pub fn touches_item(pos: Vec2, (col, row): Cell, block_size: usize) -> bool {
+    if col % 3 == 0 {
+        return touches_cell(pos, (col, row), block_size);
+    }
+    let first = col - (col - 1) % 3;
+    touches_cell(pos, (first, row), block_size) || touches_cell(pos, (first + 1, row), block_size)
+}
@@ -0,0 +1,358 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use crate::maze::is_passable;
+
+// Casilla de la rejilla de caracteres como (columna, fila)
+pub type Cell = (usize, usize);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Bfs,
+    AStar,
+    DeadEndFilling,
+    WallFollower(Hand),
+}
+
+pub const STRATEGY_NAMES: [&str; 5] = ["bfs", "astar", "dead-end", "left-hand", "right-hand"];
+
+pub fn strategy_by_name(name: &str) -> Option<Strategy> {
+    match name {
+        "bfs" => Some(Strategy::Bfs),
+        "astar" => Some(Strategy::AStar),
+        "dead-end" => Some(Strategy::DeadEndFilling),
+        "left-hand" => Some(Strategy::WallFollower(Hand::Left)),
+        "right-hand" => Some(Strategy::WallFollower(Hand::Right)),
+        _ => None,
+    }
+}
+
+// Norte, este, sur, oeste: girar a la derecha es sumar 1
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub fn find_cell(maze: &[Vec<char>], target: char) -> Option<Cell> {
+    for (row, line) in maze.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            if cell == target {
+                return Some((col, row));
+            }
+        }
+    }
+    None
+}
+
+// Devuelve el camino de `start` a `goal`, ambos incluidos. Con BFS, A* y
+// relleno de callejones es el más corto; el seguidor de paredes devuelve el
+// recorrido tal cual lo camina, con retrocesos incluidos.
+pub fn solve(maze: &[Vec<char>], start: Cell, goal: Cell, strategy: Strategy) -> Option<Vec<Cell>> {
+    if !walkable(maze, start) || !walkable(maze, goal) {
+        return None;
+    }
+
+    match strategy {
+        Strategy::Bfs => bfs(maze, start, goal, |_| true),
+        Strategy::AStar => a_star(maze, start, goal),
+        Strategy::DeadEndFilling => dead_end_filling(maze, start, goal),
+        Strategy::WallFollower(hand) => wall_follower(maze, start, goal, hand),
+    }
+}
+
+// Camino más corto que sólo pisa casillas aceptadas por `allowed`, p. ej.
+// para no cruzar puertas cerradas
+pub fn shortest_path(
+    maze: &[Vec<char>],
+    start: Cell,
+    goal: Cell,
+    allowed: impl Fn(Cell) -> bool,
+) -> Option<Vec<Cell>> {
+    if !walkable(maze, start) || !walkable(maze, goal) {
+        return None;
+    }
+    bfs(maze, start, goal, allowed)
+}
+
+// Las puertas cuentan como paso: el jugador siempre las puede abrir
+fn walkable(maze: &[Vec<char>], (col, row): Cell) -> bool {
+    maze.get(row)
+        .and_then(|line| line.get(col))
+        .is_some_and(|&cell| is_passable(cell))
+}
+
+fn step(maze: &[Vec<char>], (col, row): Cell, (dx, dy): (isize, isize)) -> Option<Cell> {
+    let ncol = col as isize + dx;
+    let nrow = row as isize + dy;
+    if ncol < 0 || nrow < 0 {
+        return None;
+    }
+    let next = (ncol as usize, nrow as usize);
+    if walkable(maze, next) {
+        Some(next)
+    } else {
+        None
+    }
+}
+
+fn neighbors(maze: &[Vec<char>], cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+    DIRECTIONS.iter().filter_map(move |&dir| step(maze, cell, dir))
+}
+
+fn width(maze: &[Vec<char>]) -> usize {
+    maze.iter().map(|line| line.len()).max().unwrap_or(0)
+}
+
+fn rebuild_path(came_from: &[Option<Cell>], width: usize, start: Cell, goal: Cell) -> Vec<Cell> {
+    let mut path = vec![goal];
+    let mut cell = goal;
+    while cell != start {
+        cell = came_from[cell.1 * width + cell.0].unwrap();
+        path.push(cell);
+    }
+    path.reverse();
+    path
+}
+
+// BFS restringido a las casillas que acepte `allowed`
+fn bfs(
+    maze: &[Vec<char>],
+    start: Cell,
+    goal: Cell,
+    allowed: impl Fn(Cell) -> bool,
+) -> Option<Vec<Cell>> {
+    let width = width(maze);
+    let mut came_from = vec![None; width * maze.len()];
+    let mut visited = vec![false; width * maze.len()];
+    let mut queue = VecDeque::new();
+
+    visited[start.1 * width + start.0] = true;
+    queue.push_back(start);
+
+    while let Some(cell) = queue.pop_front() {
+        if cell == goal {
+            return Some(rebuild_path(&came_from, width, start, goal));
+        }
+        for next in neighbors(maze, cell) {
+            let i = next.1 * width + next.0;
+            if !visited[i] && allowed(next) {
+                visited[i] = true;
+                came_from[i] = Some(cell);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+fn a_star(maze: &[Vec<char>], start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+    let width = width(maze);
+    let heuristic = |(col, row): Cell| col.abs_diff(goal.0) + row.abs_diff(goal.1);
+
+    let mut came_from = vec![None; width * maze.len()];
+    let mut cost = vec![usize::MAX; width * maze.len()];
+    let mut open = BinaryHeap::new();
+
+    cost[start.1 * width + start.0] = 0;
+    open.push(Reverse((heuristic(start), start)));
+
+    while let Some(Reverse((_, cell))) = open.pop() {
+        if cell == goal {
+            return Some(rebuild_path(&came_from, width, start, goal));
+        }
+        let current = cost[cell.1 * width + cell.0];
+        for next in neighbors(maze, cell) {
+            let i = next.1 * width + next.0;
+            if current + 1 < cost[i] {
+                cost[i] = current + 1;
+                came_from[i] = Some(cell);
+                open.push(Reverse((current + 1 + heuristic(next), next)));
+            }
+        }
+    }
+
+    None
+}
+
+// Rellena los callejones sin salida hasta que sólo quedan las casillas que
+// llevan de `start` a `goal`, y luego recorre lo que sobrevive. Se trabaja con
+// las celdas del laberinto (3x2 caracteres): en la rejilla de caracteres los
+// pasillos tienen dos de ancho y ninguna casilla parece un callejón.
+fn dead_end_filling(maze: &[Vec<char>], start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+    let columns = width(maze) / 3;
+    let rows = maze.len() / 2;
+    let index = |(col, row): Cell| row * columns + col;
+    let exists = |(col, row): Cell| col < columns && row < rows && walkable(maze, (3 * col + 1, 2 * row + 1));
+
+    // Celdas vecinas a las que se pasa por un hueco abierto
+    let links = |(col, row): Cell| {
+        let mut next = Vec::new();
+        if col > 0 && walkable(maze, (3 * col, 2 * row + 1)) {
+            next.push((col - 1, row));
+        }
+        if walkable(maze, (3 * col + 3, 2 * row + 1)) {
+            next.push((col + 1, row));
+        }
+        if row > 0 && walkable(maze, (3 * col + 1, 2 * row)) {
+            next.push((col, row - 1));
+        }
+        if walkable(maze, (3 * col + 1, 2 * row + 2)) {
+            next.push((col, row + 1));
+        }
+        next.retain(|&cell| exists(cell));
+        next
+    };
+
+    let mut protected = vec![false; columns * rows];
+    for cell in owners(start).into_iter().chain(owners(goal)) {
+        if exists(cell) {
+            protected[index(cell)] = true;
+        }
+    }
+
+    let mut filled = vec![false; columns * rows];
+    let mut pending: Vec<Cell> = (0..rows)
+        .flat_map(|row| (0..columns).map(move |col| (col, row)))
+        .filter(|&cell| exists(cell))
+        .collect();
+
+    while let Some(cell) = pending.pop() {
+        let i = index(cell);
+        let open: Vec<Cell> = links(cell).into_iter().filter(|&next| !filled[index(next)]).collect();
+        if filled[i] || protected[i] || open.len() > 1 {
+            continue;
+        }
+        filled[i] = true;
+        pending.extend(open);
+    }
+
+    // Un carácter queda relleno si todas las celdas a las que pertenece lo están
+    let char_filled = |cell: Cell| {
+        let cells: Vec<Cell> = owners(cell).into_iter().filter(|&cell| exists(cell)).collect();
+        !cells.is_empty() && cells.iter().all(|&cell| filled[index(cell)])
+    };
+
+    // Las rejillas que no siguen el formato de 3x2 se resuelven sin rellenar
+    bfs(maze, start, goal, |cell| !char_filled(cell)).or_else(|| bfs(maze, start, goal, |_| true))
+}
+
+// Celdas del laberinto a las que pertenece un carácter: la suya si está dentro
+// de una celda, las dos que separa si está en un hueco y ninguna en las esquinas
+fn owners((col, row): Cell) -> Vec<Cell> {
+    match (col % 3 == 0, row % 2 == 0) {
+        (false, false) => vec![(col / 3, row / 2)],
+        (true, false) if col > 0 => vec![(col / 3 - 1, row / 2), (col / 3, row / 2)],
+        (false, true) if row > 0 => vec![(col / 3, row / 2 - 1), (col / 3, row / 2)],
+        _ => Vec::new(),
+    }
+}
+
+// Camina con una mano siempre en la pared. No termina en laberintos donde la
+// meta está en una isla, así que se corta tras recorrer cada borde varias veces.
+fn wall_follower(maze: &[Vec<char>], start: Cell, goal: Cell, hand: Hand) -> Option<Vec<Cell>> {
+    let max_steps = 4 * width(maze) * maze.len();
+    let (first_turn, turns) = match hand {
+        Hand::Left => (3, [3, 0, 1, 2]),
+        Hand::Right => (1, [1, 0, 3, 2]),
+    };
+
+    let mut path = vec![start];
+    let mut cell = start;
+    // Arrancar mirando a una dirección con pared a la mano elegida
+    let mut facing = (0..4)
+        .find(|&d| step(maze, cell, DIRECTIONS[(d + first_turn) % 4]).is_none())
+        .unwrap_or(0);
+
+    for _ in 0..max_steps {
+        if cell == goal {
+            return Some(path);
+        }
+
+        let turn = turns
+            .iter()
+            .find(|&&t| step(maze, cell, DIRECTIONS[(facing + t) % 4]).is_some())?;
+        facing = (facing + turn) % 4;
+        cell = step(maze, cell, DIRECTIONS[facing]).unwrap();
+        path.push(cell);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::braid::add_loops;
+    use crate::maze::generate;
+    use crate::rng::Rng;
+
+    fn endpoints(maze: &[Vec<char>]) -> (Cell, Cell) {
+        (find_cell(maze, 'p').unwrap(), find_cell(maze, 'g').unwrap())
+    }
+
+    // Cada paso del camino avanza a una casilla vecina por la que se puede pasar
+    fn is_walk(maze: &[Vec<char>], path: &[Cell], start: Cell, goal: Cell) -> bool {
+        path.first() == Some(&start)
+            && path.last() == Some(&goal)
+            && path.iter().all(|&cell| walkable(maze, cell))
+            && path.windows(2).all(|pair| pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1) == 1)
+    }
+
+    #[test]
+    fn shortest_strategies_agree_on_length() {
+        for seed in 0..20 {
+            let mut maze = generate(12, 8, seed);
+            // Con ciclos hay más de un camino y el más corto deja de ser el único
+            if seed % 2 == 1 {
+                add_loops(&mut maze, 15, &mut Rng::new(seed));
+            }
+            let (start, goal) = endpoints(&maze);
+
+            let bfs = solve(&maze, start, goal, Strategy::Bfs).unwrap();
+            assert!(is_walk(&maze, &bfs, start, goal));
+            for strategy in [Strategy::AStar, Strategy::DeadEndFilling] {
+                let path = solve(&maze, start, goal, strategy).unwrap();
+                assert!(is_walk(&maze, &path, start, goal), "seed {}", seed);
+                assert_eq!(path.len(), bfs.len(), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn wall_follower_solves_perfect_maze() {
+        for seed in 0..20 {
+            let maze = generate(12, 8, seed);
+            let (start, goal) = endpoints(&maze);
+            for hand in [Hand::Left, Hand::Right] {
+                let path = solve(&maze, start, goal, Strategy::WallFollower(hand)).unwrap();
+                assert!(is_walk(&maze, &path, start, goal), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn wall_follower_gives_up_on_island_goal() {
+        // La meta está en medio de la sala, lejos de cualquier pared
+        let maze: Vec<Vec<char>> = [
+            "+-----+",
+            "|p    |",
+            "|     |",
+            "|  g  |",
+            "|     |",
+            "|     |",
+            "+-----+",
+        ]
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect();
+        let (start, goal) = endpoints(&maze);
+
+        assert!(solve(&maze, start, goal, Strategy::Bfs).is_some());
+        for hand in [Hand::Left, Hand::Right] {
+            assert!(solve(&maze, start, goal, Strategy::WallFollower(hand)).is_none());
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use nalgebra_glm::Vec2;
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::Fog;
+use crate::player::Player;
+use crate::texture::Texture;
+
+// Objeto del mundo dibujado como un cartel que siempre mira a la cámara.
+// `scale` es la altura en fracciones de pared; el sprite se apoya en el piso.
+pub struct Sprite<'a> {
+    pub pos: Vec2,
+    pub texture: &'a Texture,
+    pub scale: f32,
+}
+
+// Dibuja los sprites de atrás hacia adelante. `zbuffer` trae, por columna, la
+// distancia perpendicular a la pared que dejó `render3d`: sólo se pintan las
+// columnas del sprite que quedan delante de ella.
+pub fn render_sprites(
+    framebuffer: &mut Framebuffer,
+    player: &Player,
+    sprites: &mut [Sprite],
+    zbuffer: &[f32],
+    block_size: usize,
+    fog: &Fog,
+) {
+    let block = block_size as f32;
+    let distance_to_projection_plane = player.projection_plane_distance(framebuffer.width);
+    let horizon = player.horizon(framebuffer.height, distance_to_projection_plane);
+    let eye_height = player.eye_height() * block;
+
+    let forward = Vec2::new(player.a.cos(), player.a.sin());
+    let right = Vec2::new(-player.a.sin(), player.a.cos());
+
+    let depth = |sprite: &Sprite| (sprite.pos - player.pos).dot(&forward);
+    sprites.sort_by(|a, b| depth(b).total_cmp(&depth(a)));
+
+    for sprite in sprites.iter() {
+        let relative = sprite.pos - player.pos;
+        let depth = relative.dot(&forward);
+        if depth < 1.0 {
+            continue;
+        }
+
+        let screen_x = framebuffer.width as f32 / 2.0 + relative.dot(&right) / depth * distance_to_projection_plane;
+        let size = sprite.scale * block * distance_to_projection_plane / depth;
+        let floor_y = horizon + eye_height * distance_to_projection_plane / depth;
+
+        let left = screen_x - size / 2.0;
+        let top = floor_y - size;
+
+        let first_column = left.max(0.0) as usize;
+        let last_column = (left + size).min(framebuffer.width as f32).max(0.0) as usize;
+        let first_row = top.max(0.0) as usize;
+        let last_row = floor_y.min(framebuffer.height as f32).max(0.0) as usize;
+
+        let texture = sprite.texture;
+        let visibility = fog.visibility(depth / block);
+
+        for x in first_column..last_column {
+            if zbuffer.get(x).is_some_and(|&wall| wall < depth) {
+                continue;
+            }
+
+            let tex_x = ((x as f32 - left) / size * texture.width as f32) as usize;
+            for y in first_row..last_row {
+                let tex_y = ((y as f32 - top) / size * texture.height as f32) as usize;
+                if texture.is_opaque(tex_x, tex_y) {
+                    framebuffer.point(x, y, fog.apply(texture.get(tex_x, tex_y), visibility, 1.0));
+                }
+            }
+        }
+    }
+}
+
+// Esfera brillante que marca la meta: centro claro que se oscurece hacia el borde
+pub fn goal_texture() -> Texture {
+    let size = 64;
+    let radius = size as f32 / 2.0;
+    Texture::from_fn(size, size, |x, y| {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let distance = (dx * dx + dy * dy).sqrt() / radius;
+        if distance > 1.0 {
+            return None;
+        }
+        let glow = 1.0 - distance * distance;
+        Some(Color::new(255, (80.0 + 175.0 * glow) as u8, (40.0 + 160.0 * glow * glow) as u8))
+    })
+}
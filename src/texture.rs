@@ -0,0 +1,74 @@
+use image::GenericImageView;
+use crate::color::Color;
+
+// Bit alto de cada píxel guardado: 1 si es opaco. Sólo los sprites lo usan.
+const OPAQUE: u32 = 0xFF00_0000;
+
+// Imagen en memoria con sus dimensiones, para no suponer que todas miden 128x128
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    data: Vec<u32>,
+}
+
+impl Texture {
+    // Falla si el archivo no existe o no es una imagen que se pueda decodificar
+    pub fn load(file_path: &str) -> image::ImageResult<Self> {
+        let img = image::open(file_path)?;
+        let (width, height) = img.dimensions();
+        let mut data = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = img.get_pixel(x, y);
+                let color = ((pixel[0] as u32) << 16) | ((pixel[1] as u32) << 8) | (pixel[2] as u32);
+                let alpha = if pixel[3] >= 128 { OPAQUE } else { 0 };
+                data.push(alpha | color);
+            }
+        }
+
+        Ok(Texture {
+            width: width as usize,
+            height: height as usize,
+            data,
+        })
+    }
+
+    // Textura de un solo píxel, para superficies de color liso
+    pub fn solid(color: Color) -> Self {
+        Texture {
+            width: 1,
+            height: 1,
+            data: vec![OPAQUE | color.to_hex()],
+        }
+    }
+
+    // Textura generada por código; `None` deja el píxel transparente
+    pub fn from_fn(width: usize, height: usize, pixel: impl Fn(usize, usize) -> Option<Color>) -> Self {
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(pixel(x, y).map(|color| OPAQUE | color.to_hex()).unwrap_or(0));
+            }
+        }
+
+        Texture { width, height, data }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        self.raw(x, y) & !OPAQUE
+    }
+
+    pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+        self.raw(x, y) & OPAQUE != 0
+    }
+
+    fn raw(&self, x: usize, y: usize) -> u32 {
+        self.data[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+    // `u` y `v` van de 0.0 a 1.0 a lo ancho y alto de la imagen
+    pub fn sample(&self, u: f32, v: f32) -> u32 {
+        self.get((u * self.width as f32) as usize, (v * self.height as f32) as usize)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::collectibles::Collectibles;
+use crate::doors::Doors;
+use crate::enemies::Enemies;
+use crate::keys::Keys;
+
+// Lo que cambia durante la partida: la rejilla ya sin objetos, las puertas,
+// lo que queda por recoger y los enemigos
+pub struct World {
+    pub maze: Vec<Vec<char>>,
+    pub doors: Doors,
+    pub keys: Keys,
+    pub coins: Collectibles,
+    pub enemies: Enemies,
+}
+
+impl World {
+    // Las llaves, monedas y enemigos pasan a ser objetos; en la rejilla queda piso
+    pub fn new(mut maze: Vec<Vec<char>>, block_size: usize, seed: u64) -> Self {
+        let keys = Keys::extract(&mut maze);
+        let coins = Collectibles::extract(&mut maze);
+        let enemies = Enemies::extract(&mut maze, block_size, seed);
+        let doors = Doors::from_maze(&maze);
+        World { maze, doors, keys, coins, enemies }
+    }
+}